@@ -0,0 +1,314 @@
+use std::ops::Range;
+
+use aho_corasick::{AhoCorasick, BuildError, Input, MatchKind};
+
+use crate::{KeeperPattern, StringKeeper};
+
+pub trait KeeperAnyExt<P> {
+    /// Like [`keep`](crate::StringKeeperCommonExt::keep), but matches whichever of `patterns`
+    /// occurs first.
+    ///
+    /// # Panics
+    ///
+    /// Like [`AnyPattern::new`]; see [`try_keep_any`](KeeperAnyExt::try_keep_any).
+    fn keep_any<I>(self, patterns: I) -> StringKeeper<AnyPattern, P>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>;
+
+    /// Like [`cut`](crate::StringKeeperCommonExt::cut), but matches whichever of `patterns`
+    /// occurs first.
+    ///
+    /// # Panics
+    ///
+    /// Like [`AnyPattern::new`]; see [`try_cut_any`](KeeperAnyExt::try_cut_any).
+    fn cut_any<I>(self, patterns: I) -> StringKeeper<AnyPattern, P>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>;
+
+    fn try_keep_any<I>(self, patterns: I) -> Result<StringKeeper<AnyPattern, P>, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>;
+
+    fn try_cut_any<I>(self, patterns: I) -> Result<StringKeeper<AnyPattern, P>, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>;
+}
+
+/// Which alternative wins when several of them match at the same position.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AnyMatchKind {
+    /// The alternative listed first, like a regex alternation.
+    LeftmostFirst,
+    /// The longest alternative.
+    LeftmostLongest,
+}
+
+/// A set of string alternatives searched for in a single pass with an Aho-Corasick automaton.
+///
+/// The matches are found like those of `regex::Regex`: leftmost, non-overlapping, and the
+/// [`AnyMatchKind`] decides between alternatives starting at the same position. Alternatives
+/// always match exactly, whatever the [`KeeperMatching`](crate::KeeperMatching) mode.
+#[derive(Clone, Debug)]
+pub struct AnyPattern {
+    patterns: Vec<String>,
+    kind: AnyMatchKind,
+    automaton: AhoCorasick,
+}
+
+impl<P> KeeperAnyExt<P> for P {
+    fn keep_any<I>(self, patterns: I) -> StringKeeper<AnyPattern, P>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        crate::StringKeeperCommonExt::keep(self, AnyPattern::new(patterns))
+    }
+
+    fn cut_any<I>(self, patterns: I) -> StringKeeper<AnyPattern, P>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        crate::StringKeeperCommonExt::cut(self, AnyPattern::new(patterns))
+    }
+
+    fn try_keep_any<I>(self, patterns: I) -> Result<StringKeeper<AnyPattern, P>, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        AnyPattern::try_new(patterns).map(|pattern| crate::StringKeeperCommonExt::keep(self, pattern))
+    }
+
+    fn try_cut_any<I>(self, patterns: I) -> Result<StringKeeper<AnyPattern, P>, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        AnyPattern::try_new(patterns).map(|pattern| crate::StringKeeperCommonExt::cut(self, pattern))
+    }
+}
+
+impl AnyPattern {
+    /// Builds a leftmost-first set.
+    ///
+    /// # Panics
+    ///
+    /// If the automaton exceeds the size limits of the `aho-corasick` crate; use
+    /// [`try_new`](AnyPattern::try_new) for alternatives that are not known in advance.
+    pub fn new<I>(patterns: I) -> AnyPattern
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        AnyPattern::try_new(patterns).expect("alternatives exceed the aho-corasick size limits")
+    }
+
+    pub fn try_new<I>(patterns: I) -> Result<AnyPattern, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let patterns = patterns.into_iter().map(Into::into).collect::<Vec<String>>();
+        AnyPattern::build(patterns, AnyMatchKind::LeftmostFirst)
+    }
+
+    fn build(patterns: Vec<String>, kind: AnyMatchKind) -> Result<AnyPattern, BuildError> {
+        let match_kind = match kind {
+            AnyMatchKind::LeftmostFirst => MatchKind::LeftmostFirst,
+            AnyMatchKind::LeftmostLongest => MatchKind::LeftmostLongest,
+        };
+        let automaton = AhoCorasick::builder().match_kind(match_kind).build(&patterns)?;
+        Ok(AnyPattern { patterns, kind, automaton })
+    }
+
+    /// Rebuilds the automaton for another [`AnyMatchKind`].
+    ///
+    /// # Panics
+    ///
+    /// Like [`new`](AnyPattern::new), if the rebuilt automaton exceeds the size limits.
+    pub fn set_match_kind(self, kind: AnyMatchKind) -> AnyPattern {
+        if self.kind == kind {
+            self
+        } else {
+            AnyPattern::build(self.patterns, kind).expect("alternatives exceed the aho-corasick size limits")
+        }
+    }
+
+    pub fn leftmost_first(self) -> AnyPattern {
+        self.set_match_kind(AnyMatchKind::LeftmostFirst)
+    }
+
+    pub fn leftmost_longest(self) -> AnyPattern {
+        self.set_match_kind(AnyMatchKind::LeftmostLongest)
+    }
+
+    pub fn match_kind(&self) -> &AnyMatchKind {
+        &self.kind
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// The index of the alternative that produced the first match inside `matched`.
+    pub fn alternative(&self, haystack: &str, matched: Range<usize>) -> Option<usize> {
+        self
+            .automaton
+            .find(Input::new(haystack).range(matched))
+            .map(|found| found.pattern().as_usize())
+    }
+}
+
+impl PartialEq for AnyPattern {
+    fn eq(&self, other: &AnyPattern) -> bool {
+        self.patterns == other.patterns && self.kind == other.kind
+    }
+}
+
+impl Eq for AnyPattern {}
+
+impl KeeperPattern for AnyPattern {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self
+            .automaton
+            .find(Input::new(haystack).range(from..))
+            .map(|found| found.range())
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self
+            .automaton
+            .find_iter(Input::new(haystack).range(..to))
+            .last()
+            .map(|found| found.range())
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self
+            .automaton
+            .find_iter(Input::new(haystack).range(..at))
+            .filter(|found| found.end() == at)
+            .last()
+            .map(|found| found.start())
+    }
+}
+
+impl<P> StringKeeper<AnyPattern, P> {
+    /// Prefers the alternative listed first, for the pattern and the until pattern. This is the
+    /// default.
+    pub fn leftmost_first(self) -> StringKeeper<AnyPattern, P> {
+        self.set_match_kind(AnyMatchKind::LeftmostFirst)
+    }
+
+    /// Prefers the longest alternative, for the pattern and the until pattern.
+    pub fn leftmost_longest(self) -> StringKeeper<AnyPattern, P> {
+        self.set_match_kind(AnyMatchKind::LeftmostLongest)
+    }
+
+    pub fn set_match_kind(self, kind: AnyMatchKind) -> StringKeeper<AnyPattern, P> {
+        self.map_spec(|mut spec| {
+            spec.pattern = spec.pattern.set_match_kind(kind.clone());
+            spec.until_pattern = spec.until_pattern.map(|until_pattern| until_pattern.set_match_kind(kind));
+            spec
+        })
+    }
+}
+
+impl<P: AsRef<str>> StringKeeper<AnyPattern, P> {
+    /// The index of the alternative the selected region was found with, or `None` when the
+    /// pattern is absent.
+    pub fn matched_alternative(&self) -> Option<usize> {
+        let to_parse = self.to_parse.as_ref();
+        let found = self.spec.locate(to_parse).ok()?;
+        self.spec.pattern.alternative(to_parse, found.pattern)
+    }
+
+    /// The text of the alternative the selected region was found with.
+    pub fn matched_pattern(&self) -> Option<&str> {
+        self
+            .matched_alternative()
+            .map(|idx| self.spec.pattern.patterns[idx].as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{AnyPattern, KeeperSpec};
+
+    #[test]
+    fn keep_after_first_alternative() {
+        let log = "boot ok; WARN: disk karøbα; ERROR: it was";
+        let keeper = log.keep_any(["ERROR", "WARN", "FATAL"]).after_pattern().excluding_pattern();
+        assert_eq!(keeper.to_string(), ": disk karøbα; ERROR: it was");
+        assert_eq!(keeper.matched_pattern(), Some("WARN"));
+        assert_eq!(keeper.matched_alternative(), Some(1));
+
+        let keeper = keeper.end_of_string();
+        assert_eq!(keeper.to_string(), ": it was");
+        assert_eq!(keeper.matched_pattern(), Some("ERROR"));
+
+        let missing = "all good".keep_any(["ERROR", "WARN"]);
+        assert_eq!(missing.to_string(), "");
+        assert_eq!(missing.matched_alternative(), None);
+    }
+
+    #[test]
+    fn leftmost_first_and_longest() {
+        let text = "karøbα Samwise";
+        let first = text.keep_any(["Sam", "Samwise"]).after_pattern().excluding_pattern();
+        assert_eq!(first.to_string(), "wise");
+        assert_eq!(first.matched_pattern(), Some("Sam"));
+
+        let longest = first.leftmost_longest();
+        assert_eq!(longest.to_string(), "");
+        assert_eq!(longest.matched_pattern(), Some("Samwise"));
+        assert_eq!(longest.leftmost_first().matched_alternative(), Some(0));
+    }
+
+    #[test]
+    fn cut_any_and_until_alternatives() {
+        assert_eq!(
+            "key: karøbα\r\nrest".cut_any(["\r\n", "\n"]).after_pattern().including_pattern().into_cow(),
+            "key: karøbα"
+        );
+        assert_eq!(
+            "a=1; b=karøbα, c=3"
+                .keep_any(["b=", "x="])
+                .after_pattern()
+                .excluding_pattern()
+                .until_first_matched_pattern(AnyPattern::new([";", ","]))
+                .to_string(),
+            "karøbα"
+        );
+    }
+
+    #[test]
+    fn occurrences_of_any_alternative() {
+        let keeper = "1 WARN a, 2 ERROR b, 3 WARN c"
+            .keep_any(["ERROR", "WARN"])
+            .after_pattern()
+            .excluding_pattern()
+            .until_first_matched_pattern(AnyPattern::new([",", "!"]));
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), [" a", " b"]);
+        assert_eq!(keeper.nth_occurrence(1).matched_pattern(), Some("ERROR"));
+
+        let spec = KeeperSpec::keep(AnyPattern::new(["ERROR", "WARN"]).leftmost_longest()).end_of_string();
+        assert_eq!(spec.apply("WARN a ERROR b"), "ERROR b");
+    }
+
+    #[test]
+    fn fallible_construction() {
+        let keeper = "WARN a, ERROR b".try_keep_any(["ERROR", "WARN"]).unwrap().end_of_string();
+        assert_eq!(keeper.to_string(), "ERROR b");
+        assert_eq!("key: karøbα\nrest".try_cut_any(["\n"]).unwrap().after_pattern().to_string(), "key: karøbα");
+        assert_eq!(AnyPattern::try_new(["karøbα"]).unwrap(), AnyPattern::new(["karøbα"]));
+    }
+}
@@ -0,0 +1,254 @@
+use std::ops::Range;
+
+use crate::{KeeperClusivity, KeeperError, KeeperPattern, KeeperPeriod};
+
+pub trait BetweenExt<O, C, P> {
+    /// Selects the text between `open` and the next `close`, e.g. the contents of `(...)`.
+    /// Delimiters are excluded by default.
+    fn between(self, open: O, close: C) -> Between<O, C, P>;
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+pub struct Between<O, C, P> {
+    to_parse: P,
+    open: O,
+    close: C,
+    nested: bool,
+    escape: Option<char>,
+    period: KeeperPeriod,
+    clusivity: KeeperClusivity,
+}
+
+impl<O, C, P> BetweenExt<O, C, P> for P {
+    fn between(self, open: O, close: C) -> Between<O, C, P> {
+        Between {
+            to_parse: self,
+            open,
+            close,
+            nested: false,
+            escape: None,
+            period: KeeperPeriod::Start,
+            clusivity: KeeperClusivity::Excluding,
+        }
+    }
+}
+
+impl<O, C, P> Between<O, C, P> {
+    /// Tracks nesting depth, so `close` only ends the group once every inner `open` is balanced.
+    pub fn nested(mut self) -> Between<O, C, P> {
+        self.nested = true;
+        self
+    }
+
+    /// Delimiters directly preceded by an odd number of `escape` chars are treated as text.
+    pub fn escape_char(mut self, escape: char) -> Between<O, C, P> {
+        self.escape = Some(escape);
+        self
+    }
+
+    pub fn beginning_of_string(mut self) -> Between<O, C, P> {
+        self.period = KeeperPeriod::Start;
+        self
+    }
+
+    pub fn end_of_string(mut self) -> Between<O, C, P> {
+        self.period = KeeperPeriod::End;
+        self
+    }
+
+    pub fn including_pattern(mut self) -> Between<O, C, P> {
+        self.clusivity = KeeperClusivity::Including;
+        self
+    }
+
+    pub fn excluding_pattern(mut self) -> Between<O, C, P> {
+        self.clusivity = KeeperClusivity::Excluding;
+        self
+    }
+}
+
+struct Group {
+    open: Range<usize>,
+    close: Range<usize>,
+}
+
+impl<O: KeeperPattern, C: KeeperPattern, P: AsRef<str>> Between<O, C, P> {
+    fn is_escaped(&self, to_parse: &str, at: usize) -> bool {
+        self.escape.is_some_and(|escape| {
+            to_parse[..at].chars().rev().take_while(|c| *c == escape).count() % 2 == 1
+        })
+    }
+
+    fn next_unescaped(&self, pattern: &impl KeeperPattern, to_parse: &str, mut from: usize) -> Option<Range<usize>> {
+        loop {
+            let found = pattern.next_match(to_parse, from)?;
+            if !self.is_escaped(to_parse, found.start) && !found.is_empty() {
+                return Some(found);
+            }
+            from = found.start + to_parse[found.start..].chars().next()?.len_utf8();
+        }
+    }
+
+    fn next_group(&self, to_parse: &str, from: usize) -> Result<Group, KeeperError> {
+        let open = self
+            .next_unescaped(&self.open, to_parse, from)
+            .ok_or(KeeperError::PatternNotFound)?;
+        let mut depth = 1usize;
+        let mut pos = open.end;
+        loop {
+            let close = self
+                .next_unescaped(&self.close, to_parse, pos)
+                .ok_or(KeeperError::UntilPatternNotFound)?;
+            let inner = self
+                .nested
+                .then(|| self.next_unescaped(&self.open, to_parse, pos))
+                .flatten()
+                .filter(|inner| inner.start < close.start);
+            match inner {
+                Some(inner) => {
+                    depth += 1;
+                    pos = inner.end;
+                }
+                None => {
+                    depth -= 1;
+                    pos = close.end;
+                    if depth == 0 {
+                        return Ok(Group { open, close });
+                    }
+                }
+            }
+        }
+    }
+
+    fn group_range(&self, group: Group) -> Range<usize> {
+        match self.clusivity {
+            KeeperClusivity::Including => group.open.start..group.close.end,
+            KeeperClusivity::Excluding => group.open.end..group.close.start,
+        }
+    }
+
+    fn group_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let to_parse = self.to_parse.as_ref();
+        let mut cursor = Some(usize::MIN);
+        std::iter::from_fn(move || {
+            let group = self.next_group(to_parse, cursor.take()?).ok()?;
+            cursor = Some(group.close.end);
+            Some(self.group_range(group))
+        })
+    }
+
+    fn locate(&self) -> Result<Range<usize>, KeeperError> {
+        let to_parse = self.to_parse.as_ref();
+        match self.period {
+            KeeperPeriod::Start => self.next_group(to_parse, usize::MIN).map(|group| self.group_range(group)),
+            KeeperPeriod::End => match self.group_ranges().last() {
+                Some(range) => Ok(range),
+                None => self.next_group(to_parse, usize::MIN).map(|group| self.group_range(group)),
+            },
+        }
+    }
+
+    /// Every top-level balanced group, in order. An unclosed group ends the iteration.
+    pub fn groups(&self) -> impl Iterator<Item = &str> + '_ {
+        let to_parse = self.to_parse.as_ref();
+        self.group_ranges().map(move |range| &to_parse[range])
+    }
+
+    /// The byte range of the selected group, or `None` when there is none.
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.locate().ok()
+    }
+
+    /// The first (or with [`end_of_string`](Between::end_of_string) the last) group, borrowed from
+    /// the input. A missing `open` reports [`KeeperError::PatternNotFound`], a missing `close`
+    /// [`KeeperError::UntilPatternNotFound`].
+    pub fn try_as_str(&self) -> Result<&str, KeeperError> {
+        self.locate().map(|range| &self.to_parse.as_ref()[range])
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.try_as_str().unwrap_or_default()
+    }
+}
+
+impl<'a, O: KeeperPattern, C: KeeperPattern> Between<O, C, &'a str> {
+    /// Like [`as_str`](Between::as_str), but borrows from the original `&'a str`.
+    pub fn into_str(self) -> &'a str {
+        self.try_into_str().unwrap_or_default()
+    }
+
+    pub fn try_into_str(self) -> Result<&'a str, KeeperError> {
+        self.locate().map(|range| &self.to_parse[range])
+    }
+}
+
+impl<O: KeeperPattern, C: KeeperPattern, P: AsRef<str>> std::fmt::Display for Between<O, C, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::KeeperError;
+
+    #[test]
+    fn between_delimiters() {
+        assert_eq!("call(karøbα) it was".between('(', ')').to_string(), "karøbα");
+        assert_eq!("call(karøbα) it was".between('(', ')').including_pattern().to_string(), "(karøbα)");
+        assert_eq!("<b>karøbα</b>".between("<b>", "</b>").into_str(), "karøbα");
+        assert_eq!(
+            "[a] [bø] [c".between('[', ']').groups().collect::<Vec<_>>(),
+            ["a", "bø"]
+        );
+        assert_eq!("[a] [bø] [c".between('[', ']').end_of_string().as_str(), "bø");
+    }
+
+    #[test]
+    fn between_nested() {
+        let text = "f(a, g(b, h(c)), d) + (e)";
+        assert_eq!(text.between('(', ')').as_str(), "a, g(b, h(c");
+        assert_eq!(text.between('(', ')').nested().as_str(), "a, g(b, h(c)), d");
+        assert_eq!(
+            text.between('(', ')').nested().groups().collect::<Vec<_>>(),
+            ["a, g(b, h(c)), d", "e"]
+        );
+        assert_eq!(text.between('(', ')').nested().end_of_string().as_str(), "e");
+        assert_eq!("{ {x}".between('{', '}').nested().try_as_str(), Err(KeeperError::UntilPatternNotFound));
+    }
+
+    #[test]
+    fn between_escaped() {
+        let text = r#"say "kar\"øbα" and "\\" then"#;
+        assert_eq!(text.between('"', '"').escape_char('\\').as_str(), r#"kar\"øbα"#);
+        assert_eq!(
+            text.between('"', '"').escape_char('\\').groups().collect::<Vec<_>>(),
+            [r#"kar\"øbα"#, r"\\"]
+        );
+        assert_eq!(r"\(x) (y)".between('(', ')').escape_char('\\').as_str(), "y");
+    }
+
+    #[test]
+    fn between_missing() {
+        assert_eq!("karøbα".between('(', ')').try_as_str(), Err(KeeperError::PatternNotFound));
+        assert_eq!("(karøbα".between('(', ')').try_as_str(), Err(KeeperError::UntilPatternNotFound));
+        assert_eq!("(karøbα".between('(', ')').to_string(), "");
+        assert_eq!("()".between('(', ')').range(), Some(1..1));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn between_regex_delimiters() {
+        let open = regex::Regex::new(r"BEGIN \d+:").unwrap();
+        let close = regex::Regex::new(r"END\b").unwrap();
+        assert_eq!(
+            "BEGIN 1: karøbα END BEGIN 22: it was END"
+                .between(&open, &close)
+                .groups()
+                .map(str::trim)
+                .collect::<Vec<_>>(),
+            ["karøbα", "it was"]
+        );
+    }
+}
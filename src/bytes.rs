@@ -0,0 +1,265 @@
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::ops::{Range, RangeBounds};
+use std::path::{Path, PathBuf};
+
+use crate::{StringKeeper, StringKeeperCommonExt};
+
+/// Char-indexed substrings for byte strings, OS strings and paths.
+///
+/// Valid UTF-8 (or UTF-16 on Windows) characters count as one index each, and so does every
+/// invalid sequence that a lossy conversion would replace with `U+FFFD`. The plain methods keep the
+/// original bytes untouched; the `_lossy` methods return the same region as a `String`.
+pub trait ByteSubstringExt {
+    type Owned: Default;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> Self::Owned;
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<Self::Owned>;
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String;
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String>;
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>>;
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>>;
+}
+
+fn utf8_boundaries(bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    bytes
+        .utf8_chunks()
+        .scan(usize::MIN, |base, chunk| {
+            let start = *base;
+            *base += chunk.valid().len() + chunk.invalid().len();
+            let valid = chunk.valid().char_indices().map(move |(pos, _)| start + pos);
+            let invalid = (!chunk.invalid().is_empty()).then_some(start + chunk.valid().len());
+            Some(valid.chain(invalid))
+        })
+        .flatten()
+}
+
+fn utf8_unit_range<R: RangeBounds<usize>>(bytes: &[u8], range: R) -> Option<Range<usize>> {
+    let (start_idx, take_count) = crate::take_bounds(range)?;
+    Some(crate::unit_range(utf8_boundaries(bytes), bytes.len(), start_idx, take_count))
+}
+
+impl ByteSubstringExt for [u8] {
+    type Owned = Vec<u8>;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> Vec<u8> {
+        self.try_substring(range).unwrap_or_default()
+    }
+
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<Vec<u8>> {
+        utf8_unit_range(self, range).map(|range| self[range].to_vec())
+    }
+
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substring_lossy(range).unwrap_or_default()
+    }
+
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        utf8_unit_range(self, range).map(|range| String::from_utf8_lossy(&self[range]).into_owned())
+    }
+
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        String::from_utf8_lossy(self).keep(pattern)
+    }
+
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        String::from_utf8_lossy(self).cut(pattern)
+    }
+}
+
+#[cfg(unix)]
+impl ByteSubstringExt for OsStr {
+    type Owned = OsString;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> OsString {
+        self.try_substring(range).unwrap_or_default()
+    }
+
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<OsString> {
+        use std::os::unix::ffi::OsStrExt;
+
+        self
+            .as_bytes()
+            .try_substring(range)
+            .map(|bytes| OsStr::from_bytes(&bytes).to_os_string())
+    }
+
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substring_lossy(range).unwrap_or_default()
+    }
+
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        use std::os::unix::ffi::OsStrExt;
+
+        self.as_bytes().try_substring_lossy(range)
+    }
+
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().keep(pattern)
+    }
+
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().cut(pattern)
+    }
+}
+
+#[cfg(windows)]
+impl ByteSubstringExt for OsStr {
+    type Owned = OsString;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> OsString {
+        self.try_substring(range).unwrap_or_default()
+    }
+
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<OsString> {
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+        let wide = self.encode_wide().collect::<Vec<u16>>();
+        utf16_unit_range(&wide, range).map(|range| OsString::from_wide(&wide[range]))
+    }
+
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substring_lossy(range).unwrap_or_default()
+    }
+
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        use std::os::windows::ffi::OsStrExt;
+
+        let wide = self.encode_wide().collect::<Vec<u16>>();
+        utf16_unit_range(&wide, range).map(|range| String::from_utf16_lossy(&wide[range]))
+    }
+
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().keep(pattern)
+    }
+
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().cut(pattern)
+    }
+}
+
+#[cfg(windows)]
+fn utf16_unit_range<R: RangeBounds<usize>>(wide: &[u16], range: R) -> Option<Range<usize>> {
+    let (start_idx, take_count) = crate::take_bounds(range)?;
+    let boundaries = char::decode_utf16(wide.iter().copied())
+        .scan(usize::MIN, |base, unit| {
+            let start = *base;
+            *base += unit.map_or(1, char::len_utf16);
+            Some(start)
+        });
+    Some(crate::unit_range(boundaries, wide.len(), start_idx, take_count))
+}
+
+#[cfg(not(any(unix, windows)))]
+impl ByteSubstringExt for OsStr {
+    type Owned = OsString;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> OsString {
+        self.try_substring(range).unwrap_or_default()
+    }
+
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<OsString> {
+        self.try_substring_lossy(range).map(OsString::from)
+    }
+
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substring_lossy(range).unwrap_or_default()
+    }
+
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        use crate::SubstringExt;
+
+        self.to_string_lossy().try_substring(range)
+    }
+
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().keep(pattern)
+    }
+
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.to_string_lossy().cut(pattern)
+    }
+}
+
+impl ByteSubstringExt for Path {
+    type Owned = PathBuf;
+
+    fn substring<R: RangeBounds<usize>>(&self, range: R) -> PathBuf {
+        self.as_os_str().substring(range).into()
+    }
+
+    fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<PathBuf> {
+        self.as_os_str().try_substring(range).map(PathBuf::from)
+    }
+
+    fn substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.as_os_str().substring_lossy(range)
+    }
+
+    fn try_substring_lossy<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        self.as_os_str().try_substring_lossy(range)
+    }
+
+    fn keep_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.as_os_str().keep_lossy(pattern)
+    }
+
+    fn cut_lossy<T>(&self, pattern: T) -> StringKeeper<T, Cow<'_, str>> {
+        self.as_os_str().cut_lossy(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn byte_substring_is_lossless() {
+        let raw = b"kar\xF0\x9Fb\xCE\xB1 \xFF log".as_slice();
+        assert_eq!(raw.substring(..3), b"kar");
+        assert_eq!(raw.substring(3..5), b"\xF0\x9Fb");
+        assert_eq!(raw.substring(5..6), "α".as_bytes());
+        assert_eq!(raw.substring(7..8), b"\xFF");
+        assert_eq!(raw.substring_lossy(3..), "\u{FFFD}bα \u{FFFD} log");
+        assert_eq!(raw.try_substring(4..4), None);
+        assert_eq!(raw.substring(100..), b"");
+    }
+
+    #[test]
+    fn byte_substring_matches_str_for_valid_utf8() {
+        let text = "fõøbα®";
+        assert_eq!(text.as_bytes().substring(2..5), text.substring(2..5).as_bytes());
+        assert_eq!(text.as_bytes().substring_lossy(1..), text.substring(1..));
+    }
+
+    #[test]
+    fn os_str_and_path_substring() {
+        let file_name = OsStr::new("karøbα.tar.gz");
+        assert_eq!(file_name.substring(..6), OsStr::new("karøbα"));
+        assert_eq!(file_name.substring_lossy(7..), "tar.gz");
+
+        let path = Path::new("/var/log/karøbα.log");
+        assert_eq!(path.substring(9..), PathBuf::from("karøbα.log"));
+        assert_eq!(
+            path.keep_lossy('/').end_of_string().after_pattern().excluding_pattern().to_string(),
+            "karøbα.log"
+        );
+        assert_eq!(path.cut_lossy(".log").to_string(), "/var/log/karøbα");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_str_keeps_invalid_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let file_name = OsStr::from_bytes(b"data-\xFF.bin");
+        assert_eq!(file_name.substring(5..6).as_bytes(), b"\xFF");
+        assert_eq!(file_name.substring_lossy(5..), "\u{FFFD}.bin");
+        assert_eq!(
+            file_name.keep_lossy('.').before_pattern().excluding_pattern().to_string(),
+            "data-\u{FFFD}"
+        );
+    }
+}
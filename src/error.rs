@@ -0,0 +1,56 @@
+/// Why a [`StringKeeper`](crate::StringKeeper) could not select a region.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialOrd, PartialEq)]
+pub enum KeeperError {
+    PatternNotFound,
+    UntilPatternNotFound,
+    /// A [`KeeperPattern`](crate::KeeperPattern) reported a range that is reversed, out of bounds
+    /// or not on char boundaries.
+    InvalidRange { start: usize, end: usize },
+}
+
+impl std::fmt::Display for KeeperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeeperError::PatternNotFound => write!(f, "pattern not found"),
+            KeeperError::UntilPatternNotFound => write!(f, "until pattern not found"),
+            KeeperError::InvalidRange { start, end } => write!(f, "invalid match range {}..{}", start, end),
+        }
+    }
+}
+
+impl std::error::Error for KeeperError {}
+
+/// Why a keeper rule string could not be parsed; `position` is the char offset of the offending
+/// token.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialOrd, PartialEq)]
+pub struct RuleParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+/// Why a string is not a decimal number; `position` is the char offset of the offending char, or
+/// where the missing digits were expected.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialOrd, PartialEq)]
+pub enum NumberError {
+    InvalidChar { position: usize },
+    MissingDigits { position: usize },
+}
+
+impl std::fmt::Display for NumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::InvalidChar { position } => write!(f, "invalid char at position {}", position),
+            NumberError::MissingDigits { position } => write!(f, "expected digits at position {}", position),
+        }
+    }
+}
+
+impl std::error::Error for NumberError {}
@@ -0,0 +1,248 @@
+use std::ops::{Range, RangeBounds};
+
+use crate::{KeeperError, KeeperPattern, KeeperResolution, KeeperSpan, StringKeeper};
+
+/// A `&str` with a prebuilt char → byte index, so repeated char-indexed lookups on one large
+/// document no longer rescan it from the start.
+///
+/// Char offsets resolve in O(1) and byte → char conversions in O(log n). UTF-16 and grapheme
+/// indices are only built on request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndexedStr<'a> {
+    text: &'a str,
+    char_starts: Vec<usize>,
+    utf16_starts: Option<Vec<usize>>,
+    #[cfg(feature = "unicode-segmentation")]
+    grapheme_starts: Option<Vec<usize>>,
+}
+
+impl<'a> IndexedStr<'a> {
+    pub fn new(text: &'a str) -> IndexedStr<'a> {
+        IndexedStr {
+            text,
+            char_starts: text.char_indices().map(|(pos, _)| pos).collect(),
+            utf16_starts: None,
+            #[cfg(feature = "unicode-segmentation")]
+            grapheme_starts: None,
+        }
+    }
+
+    pub fn with_utf16(mut self) -> IndexedStr<'a> {
+        self.utf16_starts = Some(
+            self.text
+                .chars()
+                .scan(usize::MIN, |units, c| {
+                    let start = *units;
+                    *units += c.len_utf16();
+                    Some(start)
+                })
+                .collect()
+        );
+        self
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn with_graphemes(mut self) -> IndexedStr<'a> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        self.grapheme_starts = Some(self.text.grapheme_indices(true).map(|(pos, _)| pos).collect());
+        self
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.char_starts.len()
+    }
+
+    /// The byte offset of char `char_idx`; `char_count()` maps to the end of the text.
+    pub fn byte_offset(&self, char_idx: usize) -> Option<usize> {
+        match char_idx.cmp(&self.char_count()) {
+            std::cmp::Ordering::Less => Some(self.char_starts[char_idx]),
+            std::cmp::Ordering::Equal => Some(self.text.len()),
+            std::cmp::Ordering::Greater => None,
+        }
+    }
+
+    /// The char index starting at `byte_idx`, or `None` when it is not a char boundary.
+    pub fn char_offset(&self, byte_idx: usize) -> Option<usize> {
+        if byte_idx == self.text.len() {
+            return Some(self.char_count());
+        }
+        self.char_starts.binary_search(&byte_idx).ok()
+    }
+
+    /// The UTF-16 offset of the char boundary at `byte_idx`.
+    pub fn utf16_offset(&self, byte_idx: usize) -> Option<usize> {
+        let char_idx = self.char_offset(byte_idx)?;
+        Some(match &self.utf16_starts {
+            Some(utf16_starts) => utf16_starts
+                .get(char_idx)
+                .copied()
+                .unwrap_or_else(|| self.text.encode_utf16().count()),
+            None => self.text[..byte_idx].encode_utf16().count(),
+        })
+    }
+
+    pub fn substring<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.substr(range).to_string()
+    }
+
+    pub fn try_substring<R: RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        self.try_substr(range).map(str::to_string)
+    }
+
+    pub fn substr<R: RangeBounds<usize>>(&self, range: R) -> &'a str {
+        self.try_substr(range).unwrap_or_default()
+    }
+
+    pub fn try_substr<R: RangeBounds<usize>>(&self, range: R) -> Option<&'a str> {
+        let (start_idx, take_count) = crate::take_bounds(range)?;
+        Some(&self.text[unit_range(&self.char_starts, self.text.len(), start_idx, take_count)])
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn substring_graphemes<R: RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substr_graphemes(range).unwrap_or_default().to_string()
+    }
+
+    /// Grapheme-indexed substring; falls back to scanning when built without
+    /// [`with_graphemes`](IndexedStr::with_graphemes).
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn try_substr_graphemes<R: RangeBounds<usize>>(&self, range: R) -> Option<&'a str> {
+        match &self.grapheme_starts {
+            Some(grapheme_starts) => {
+                let (start_idx, take_count) = crate::take_bounds(range)?;
+                Some(&self.text[unit_range(grapheme_starts, self.text.len(), start_idx, take_count)])
+            }
+            None => crate::SubstringExt::try_substr_graphemes(self.text, range),
+        }
+    }
+
+    /// Converts a byte range on char boundaries into a [`KeeperSpan`].
+    pub fn span(&self, bytes: Range<usize>) -> Option<KeeperSpan> {
+        Some(KeeperSpan {
+            chars: self.char_offset(bytes.start)?..self.char_offset(bytes.end)?,
+            utf16: match self.utf16_starts {
+                Some(_) => Some(self.utf16_offset(bytes.start)?..self.utf16_offset(bytes.end)?),
+                None => None,
+            },
+            bytes,
+        })
+    }
+
+    /// Like [`StringKeeper::resolve`], but converts offsets through this index. Keepers built over
+    /// a different buffer are resolved the slow way.
+    pub fn resolve<T: KeeperPattern, P: AsRef<str>>(&self, keeper: &StringKeeper<T, P>) -> Option<KeeperResolution> {
+        self.try_resolve(keeper).ok()
+    }
+
+    pub fn try_resolve<T: KeeperPattern, P: AsRef<str>>(
+        &self,
+        keeper: &StringKeeper<T, P>,
+    ) -> Result<KeeperResolution, KeeperError> {
+        let to_parse = keeper.to_parse.as_ref();
+        if !std::ptr::eq(to_parse, self.text) {
+            return keeper.try_resolve();
+        }
+
+        keeper.spec.resolve_with(to_parse, |bytes, utf16| {
+            let mut span = self
+                .span(bytes.clone())
+                .unwrap_or_else(|| KeeperSpan::new(to_parse, bytes.clone(), false));
+            span.utf16 = match (utf16, span.utf16) {
+                (true, None) => self
+                    .utf16_offset(bytes.start)
+                    .zip(self.utf16_offset(bytes.end))
+                    .map(|(start, end)| start..end),
+                (true, found) => found,
+                (false, _) => None,
+            };
+            span
+        })
+    }
+}
+
+fn unit_range(starts: &[usize], len: usize, start_idx: usize, take_count: usize) -> Range<usize> {
+    let start = starts.get(start_idx).copied().unwrap_or(len);
+    let end = starts.get(start_idx.saturating_add(take_count)).copied().unwrap_or(len);
+    start..end.max(start)
+}
+
+impl AsRef<str> for IndexedStr<'_> {
+    fn as_ref(&self) -> &str {
+        self.text
+    }
+}
+
+impl std::fmt::Display for IndexedStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexedStr;
+    use crate::prelude::*;
+
+    #[test]
+    fn indexed_substring_matches_substring() {
+        let text = "fõøbα® karøbα 𝄞 it was";
+        let indexed = IndexedStr::new(text);
+        for start in 0..=indexed.char_count() + 1 {
+            for end in start..=indexed.char_count() + 1 {
+                assert_eq!(indexed.try_substring(start..end), text.try_substring(start..end));
+            }
+        }
+        assert_eq!(indexed.substr(..=2), "fõø");
+        assert_eq!(indexed.substr(7..), "karøbα 𝄞 it was");
+    }
+
+    #[test]
+    fn offsets() {
+        let indexed = IndexedStr::new("a𝄞bα").with_utf16();
+        assert_eq!(indexed.byte_offset(2), Some(5));
+        assert_eq!(indexed.byte_offset(4), Some(8));
+        assert_eq!(indexed.byte_offset(5), None);
+        assert_eq!(indexed.char_offset(5), Some(2));
+        assert_eq!(indexed.char_offset(2), None);
+        assert_eq!(indexed.utf16_offset(5), Some(3));
+        assert_eq!(indexed.utf16_offset(8), Some(5));
+    }
+
+    #[test]
+    fn indexed_resolve_matches_resolve() {
+        let indexed = IndexedStr::new("ααα karøbα 𝄞 it was");
+        let keeper = indexed
+            .as_str()
+            .keep("karøbα")
+            .until_first_matched_pattern("it")
+            .after_pattern()
+            .excluding_pattern()
+            .utf16_encoding();
+        assert_eq!(indexed.resolve(&keeper), keeper.resolve());
+        assert_eq!(indexed.resolve(&keeper).unwrap().kept.utf16, Some(10..14));
+
+        let keeper = (&indexed).cut('ø').end_of_string();
+        assert_eq!(indexed.resolve(&keeper), keeper.resolve());
+        assert_eq!(keeper.to_string(), "ααα kar");
+
+        let other = "karøbα".to_string();
+        let keeper = other.keep('b');
+        assert_eq!(indexed.resolve(&keeper), keeper.resolve());
+        assert_eq!(indexed.resolve(&"karøbα".keep('x')), None);
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn indexed_graphemes() {
+        let text = "kar🇳🇴ã it was";
+        let indexed = IndexedStr::new(text).with_graphemes();
+        assert_eq!(indexed.try_substr_graphemes(3..5), Some("🇳🇴ã"));
+        assert_eq!(IndexedStr::new(text).try_substr_graphemes(3..5), Some("🇳🇴ã"));
+        assert_eq!(indexed.substring_graphemes(..1), "k");
+    }
+}
@@ -1,23 +1,122 @@
 #![doc = include_str ! ("./../README.md")]
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "aho-corasick")]
+mod any;
+mod between;
+mod bytes;
+mod error;
+mod indexed;
+mod matching;
+mod numeric;
+mod pattern;
+mod pipeline;
+mod rule;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod span;
+
+#[cfg(feature = "aho-corasick")]
+pub use any::{AnyMatchKind, AnyPattern, KeeperAnyExt};
+pub use between::{Between, BetweenExt};
+pub use bytes::ByteSubstringExt;
+pub use error::{KeeperError, NumberError, RuleParseError};
+pub use indexed::IndexedStr;
+pub use matching::KeeperMatching;
+use matching::Matcher;
+pub use numeric::{DecimalRounding, DigitGrouping, FixedDecimals, GroupingStyle, NumberNormalizer, NumericExt};
+pub use pattern::{KeeperPattern, RulePattern};
+pub use pipeline::KeeperPipeline;
+pub use span::{KeeperResolution, KeeperSpan};
+
+/// A keeper rule whose pattern kinds are chosen at runtime.
+pub type KeeperRule = KeeperSpec<RulePattern>;
+
 pub mod prelude {
     pub use crate::{
         SubstringExt,
+        ByteSubstringExt,
+        BetweenExt,
         StringKeeperCommonExt,
         KeeperCommonExt,
+        KeeperSpecCommonExt,
+        NumericExt,
     };
+    #[cfg(feature = "aho-corasick")]
+    pub use crate::KeeperAnyExt;
 }
 
 pub trait SubstringExt {
     fn substring<R: std::ops::RangeBounds<usize>>(&self, range: R) -> String;
     fn substring_len(&self, reverse_count: usize) -> String;
     fn try_substring<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<String>;
+    /// Removes every trailing `'0'`, so `"100"` becomes `"1"`; see
+    /// [`normalize_number`](NumericExt::normalize_number) for a number-aware version.
     fn trim_trailing_zeros(&self) -> String;
+
+    /// Like [`substring`](SubstringExt::substring), but borrows the region instead of allocating.
+    fn substr<R: std::ops::RangeBounds<usize>>(&self, range: R) -> &str;
+    fn try_substr<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<&str>;
+
+    /// Removes up to `limit` back-to-back matches of `pattern` from the start, like
+    /// `until_no_matched_pattern` widens a match. `usize::MAX` removes the whole run.
+    fn trim_run_start<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str;
+    /// Like [`trim_run_start`](SubstringExt::trim_run_start), but trims the end.
+    fn trim_run_end<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str;
+    /// Trims a run of up to `limit` matches from each end.
+    fn trim_run_both<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str;
+
+    /// Python-style `[start:stop:step]` slicing over chars. Negative indices count from the end,
+    /// out-of-range bounds are clamped like CPython and a negative `step` walks backwards. Use a
+    /// `(Bound, Bound)` tuple for descending bounds such as `[100:-100:-1]`.
+    fn slice<R: std::ops::RangeBounds<isize>>(&self, range: R, step: isize) -> String;
+    /// Like [`slice`](SubstringExt::slice), but returns `None` when `step` is zero.
+    fn try_slice<R: std::ops::RangeBounds<isize>>(&self, range: R, step: isize) -> Option<String>;
+
+    /// Like [`substring`](SubstringExt::substring), but indexes UTF-8 bytes.
+    fn substring_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> String;
+    fn try_substring_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<String>;
+    fn substr_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> &str;
+    fn try_substr_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<&str>;
+
+    /// Like [`substring`](SubstringExt::substring), but indexes UTF-16 code units.
+    fn substring_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> String;
+    fn try_substring_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<String>;
+    fn substr_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> &str;
+    fn try_substr_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<&str>;
+
+    /// Like [`substring`](SubstringExt::substring), but indexes extended grapheme clusters.
+    #[cfg(feature = "unicode-segmentation")]
+    fn substring_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> String;
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn substring_len_graphemes(&self, reverse_count: usize) -> String;
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn try_substring_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<String>;
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn substr_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> &str;
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn try_substr_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<&str>;
+}
+
+/// How byte- and UTF-16-indexed substrings treat a bound that falls inside a code point.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+pub enum BoundaryPolicy {
+    /// Return `None`.
+    Reject,
+    /// Move both bounds inwards, dropping the partial code point.
+    Shrink,
+    /// Move both bounds outwards, keeping the partial code point.
+    Expand,
 }
 
 pub trait StringKeeperCommonExt<T, P> {
     fn keep(self, pattern: T) -> StringKeeper<T, P>;
+    /// Like [`keep`](StringKeeperCommonExt::keep), but removes the selected region and returns
+    /// the remainder. The input is returned unchanged when the pattern is absent.
     fn cut(self, pattern: T) -> StringKeeper<T, P>;
 }
 
@@ -28,40 +127,72 @@ pub trait KeeperCommonExt<T, P> {
     fn excluding_pattern(self) -> StringKeeper<T, P>;
     fn before_pattern(self) -> StringKeeper<T, P>;
     fn after_pattern(self) -> StringKeeper<T, P>;
+    /// Bounds the far side of the kept region by the nearest `until_pattern` found beyond the
+    /// pattern, e.g. keep after `"BEGIN"` until the first `"END"`. Clusivity applies to both.
     fn until_first_matched_pattern(self, until_pattern: T) -> StringKeeper<T, P>;
+    /// Widens the pattern match over any run of `until_pattern` directly adjacent to it.
     fn until_no_matched_pattern(self, until_pattern: T) -> StringKeeper<T, P>;
-
-    #[cfg(feature = "regex")]
     fn utf8_encoding(self) -> StringKeeper<T, P>;
-
-    #[cfg(feature = "regex")]
     fn utf16_encoding(self) -> StringKeeper<T, P>;
-
-    #[cfg(feature = "regex")]
     fn set_encoding(self, enc: KeeperEncoding) -> StringKeeper<T, P>;
+    fn set_fallback(self, fallback: KeeperFallback) -> StringKeeper<T, P>;
+    /// Selects the `n`-th match (starting at 0) instead of the first one, counted from the end of
+    /// the input with [`end_of_string`](KeeperCommonExt::end_of_string).
+    fn nth_occurrence(self, n: usize) -> StringKeeper<T, P>;
+    /// How string and char patterns (including the until pattern) are compared with the input.
+    fn set_matching(self, matching: KeeperMatching) -> StringKeeper<T, P>;
+}
+
+/// The [`KeeperCommonExt`] options for the input-free [`KeeperSpec`]; each method behaves like
+/// its [`StringKeeper`] counterpart.
+pub trait KeeperSpecCommonExt<T> {
+    fn beginning_of_string(self) -> KeeperSpec<T>;
+    fn end_of_string(self) -> KeeperSpec<T>;
+    fn including_pattern(self) -> KeeperSpec<T>;
+    fn excluding_pattern(self) -> KeeperSpec<T>;
+    fn before_pattern(self) -> KeeperSpec<T>;
+    fn after_pattern(self) -> KeeperSpec<T>;
+    fn until_first_matched_pattern(self, until_pattern: T) -> KeeperSpec<T>;
+    fn until_no_matched_pattern(self, until_pattern: T) -> KeeperSpec<T>;
+    fn utf8_encoding(self) -> KeeperSpec<T>;
+    fn utf16_encoding(self) -> KeeperSpec<T>;
+    fn set_encoding(self, enc: KeeperEncoding) -> KeeperSpec<T>;
+    fn set_fallback(self, fallback: KeeperFallback) -> KeeperSpec<T>;
+    fn nth_occurrence(self, n: usize) -> KeeperSpec<T>;
+    fn set_matching(self, matching: KeeperMatching) -> KeeperSpec<T>;
 }
 
 pub trait StringKeeperExt<T, P>: StringKeeperCommonExt<T, P> {}
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum KeeperPeriod {
     Start,
     End,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum KeeperCutoff {
     After,
     Before,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum KeeperClusivity {
     Including,
     Excluding,
 }
 
+/// Matches are resolved to byte offsets on char boundaries, so the encoding never shifts the kept
+/// range; `Utf16` additionally reports UTF-16 ranges from [`StringKeeper::resolve`].
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum KeeperEncoding {
     Utf8,
     Utf16,
@@ -69,40 +200,82 @@ pub enum KeeperEncoding {
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum KeeperUntilMatch {
     FirstMatch,
     NoMatch,
 }
 
+/// What a keeper renders when nothing can be selected. `keep` defaults to `Empty` and `cut`
+/// defaults to `WholeInput`.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum KeeperFallback {
+    Empty,
+    WholeInput,
+    Error,
+}
+
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum StringKeeperMode {
     Cut,
     Keep,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "serde_support::StringKeeperOptsDef"))]
 pub struct StringKeeperOpts {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     until_match: Option<KeeperUntilMatch>,
     mode: StringKeeperMode,
     period: KeeperPeriod,
     clusivity: KeeperClusivity,
     cutoff: KeeperCutoff,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     encoding: Option<KeeperEncoding>,
+    fallback: KeeperFallback,
+    occurrence: usize,
+    matching: KeeperMatching,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
 pub struct StringKeeper<T, P> {
     to_parse: P,
+    spec: KeeperSpec<T>,
+}
+
+/// The pattern and options of a [`StringKeeper`] without its input, so one rule can be stored
+/// and applied to many inputs (or shared across threads when `T` is `Send + Sync`).
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_support::KeeperSpecDef<T>"))]
+pub struct KeeperSpec<T> {
     pattern: T,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     until_pattern: Option<T>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
     opt: StringKeeperOpts,
 }
 
 impl<T, P> StringKeeperCommonExt<T, P> for P {
     fn keep(self, pattern: T) -> StringKeeper<T, P> {
-        StringKeeper {
+        KeeperSpec::keep(pattern).bind(self)
+    }
+
+    fn cut(self, pattern: T) -> StringKeeper<T, P> {
+        KeeperSpec::cut(pattern).bind(self)
+    }
+}
+
+impl<T> KeeperSpec<T> {
+    pub fn keep(pattern: T) -> KeeperSpec<T> {
+        KeeperSpec {
             pattern,
-            to_parse: self,
             until_pattern: None,
             opt: StringKeeperOpts {
                 until_match: None,
@@ -111,14 +284,16 @@ impl<T, P> StringKeeperCommonExt<T, P> for P {
                 cutoff: KeeperCutoff::After,
                 clusivity: KeeperClusivity::Including,
                 encoding: None,
+                fallback: KeeperFallback::Empty,
+                occurrence: usize::MIN,
+                matching: KeeperMatching::Exact,
             },
         }
     }
 
-    fn cut(self, pattern: T) -> StringKeeper<T, P> {
-        StringKeeper {
+    pub fn cut(pattern: T) -> KeeperSpec<T> {
+        KeeperSpec {
             pattern,
-            to_parse: self,
             until_pattern: None,
             opt: StringKeeperOpts {
                 until_match: None,
@@ -127,351 +302,736 @@ impl<T, P> StringKeeperCommonExt<T, P> for P {
                 cutoff: KeeperCutoff::After,
                 clusivity: KeeperClusivity::Including,
                 encoding: None,
+                fallback: KeeperFallback::WholeInput,
+                occurrence: usize::MIN,
+                matching: KeeperMatching::Exact,
             },
         }
     }
+
+    /// Attaches an input, turning the rule back into a [`StringKeeper`].
+    pub fn bind<P>(self, to_parse: P) -> StringKeeper<T, P> {
+        StringKeeper { to_parse, spec: self }
+    }
+}
+
+impl<T, P> StringKeeper<T, P> {
+    pub fn spec(&self) -> &KeeperSpec<T> {
+        &self.spec
+    }
+
+    /// Drops the input and keeps the reusable rule.
+    pub fn into_spec(self) -> KeeperSpec<T> {
+        self.spec
+    }
+
+    fn map_spec(mut self, f: impl FnOnce(KeeperSpec<T>) -> KeeperSpec<T>) -> StringKeeper<T, P> {
+        self.spec = f(self.spec);
+        self
+    }
 }
 
 impl<T, P> KeeperCommonExt<T, P> for StringKeeper<T, P> {
-    fn beginning_of_string(mut self) -> StringKeeper<T, P> {
+    fn beginning_of_string(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::beginning_of_string)
+    }
+
+    fn end_of_string(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::end_of_string)
+    }
+
+    fn including_pattern(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::including_pattern)
+    }
+
+    fn excluding_pattern(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::excluding_pattern)
+    }
+
+    fn before_pattern(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::before_pattern)
+    }
+
+    fn after_pattern(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::after_pattern)
+    }
+
+    fn until_first_matched_pattern(self, until_pattern: T) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.until_first_matched_pattern(until_pattern))
+    }
+
+    fn until_no_matched_pattern(self, until_pattern: T) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.until_no_matched_pattern(until_pattern))
+    }
+
+    fn utf8_encoding(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::utf8_encoding)
+    }
+
+    fn utf16_encoding(self) -> StringKeeper<T, P> {
+        self.map_spec(KeeperSpec::utf16_encoding)
+    }
+
+    fn set_encoding(self, enc: KeeperEncoding) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.set_encoding(enc))
+    }
+
+    fn set_fallback(self, fallback: KeeperFallback) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.set_fallback(fallback))
+    }
+
+    fn nth_occurrence(self, n: usize) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.nth_occurrence(n))
+    }
+
+    fn set_matching(self, matching: KeeperMatching) -> StringKeeper<T, P> {
+        self.map_spec(|spec| spec.set_matching(matching))
+    }
+}
+
+impl<T> KeeperSpecCommonExt<T> for KeeperSpec<T> {
+    fn beginning_of_string(mut self) -> KeeperSpec<T> {
         self.opt.period = KeeperPeriod::Start;
         self
     }
 
-    fn end_of_string(mut self) -> StringKeeper<T, P> {
+    fn end_of_string(mut self) -> KeeperSpec<T> {
         self.opt.period = KeeperPeriod::End;
         self
     }
 
-    fn including_pattern(mut self) -> StringKeeper<T, P> {
+    fn including_pattern(mut self) -> KeeperSpec<T> {
         self.opt.clusivity = KeeperClusivity::Including;
         self
     }
 
-    fn excluding_pattern(mut self) -> StringKeeper<T, P> {
+    fn excluding_pattern(mut self) -> KeeperSpec<T> {
         self.opt.clusivity = KeeperClusivity::Excluding;
         self
     }
 
-    fn before_pattern(mut self) -> StringKeeper<T, P> {
+    fn before_pattern(mut self) -> KeeperSpec<T> {
         self.opt.cutoff = KeeperCutoff::Before;
         self
     }
 
-    fn after_pattern(mut self) -> StringKeeper<T, P> {
+    fn after_pattern(mut self) -> KeeperSpec<T> {
         self.opt.cutoff = KeeperCutoff::After;
         self
     }
 
-    fn until_first_matched_pattern(mut self, until_pattern: T) -> StringKeeper<T, P> {
+    fn until_first_matched_pattern(mut self, until_pattern: T) -> KeeperSpec<T> {
         self.until_pattern = Some(until_pattern);
         self.opt.until_match = Some(KeeperUntilMatch::FirstMatch);
         self
     }
 
-    fn until_no_matched_pattern(mut self, until_pattern: T) -> StringKeeper<T, P> {
+    fn until_no_matched_pattern(mut self, until_pattern: T) -> KeeperSpec<T> {
         self.until_pattern = Some(until_pattern);
         self.opt.until_match = Some(KeeperUntilMatch::NoMatch);
         self
     }
 
-    #[cfg(feature = "regex")]
-    fn utf8_encoding(mut self) -> StringKeeper<T, P> {
+    fn utf8_encoding(mut self) -> KeeperSpec<T> {
         self.opt.encoding = Some(KeeperEncoding::Utf8);
         self
     }
 
-    #[cfg(feature = "regex")]
-    fn utf16_encoding(mut self) -> StringKeeper<T, P> {
+    fn utf16_encoding(mut self) -> KeeperSpec<T> {
         self.opt.encoding = Some(KeeperEncoding::Utf16);
         self
     }
 
-    #[cfg(feature = "regex")]
-    fn set_encoding(mut self, enc: KeeperEncoding) -> StringKeeper<T, P> {
+    fn set_encoding(mut self, enc: KeeperEncoding) -> KeeperSpec<T> {
         self.opt.encoding = Some(enc);
         self
     }
+
+    fn set_fallback(mut self, fallback: KeeperFallback) -> KeeperSpec<T> {
+        self.opt.fallback = fallback;
+        self
+    }
+
+    fn nth_occurrence(mut self, n: usize) -> KeeperSpec<T> {
+        self.opt.occurrence = n;
+        self
+    }
+
+    fn set_matching(mut self, matching: KeeperMatching) -> KeeperSpec<T> {
+        self.opt.matching = matching;
+        self
+    }
 }
 
-impl std::fmt::Display for StringKeeper<String, String> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let try_find = match self.opt.period {
-            KeeperPeriod::Start => self.to_parse.find(&self.pattern),
-            KeeperPeriod::End => self.to_parse.rfind(&self.pattern),
-        };
+struct KeeperMatch {
+    kept: std::ops::Range<usize>,
+    pattern: std::ops::Range<usize>,
+    until_pattern: Option<std::ops::Range<usize>>,
+}
 
-        let range = match try_find {
-            None => usize::MIN..usize::MIN,
-            Some(pos) => match self.opt.clusivity {
-                KeeperClusivity::Including => match self.opt.cutoff {
-                    KeeperCutoff::After => pos..usize::MAX,
-                    KeeperCutoff::Before => {
-                        let offset = pos + self.pattern.chars().count();
-                        usize::MIN..offset
-                    }
-                },
-                KeeperClusivity::Excluding => match self.opt.cutoff {
-                    KeeperCutoff::After => {
-                        let offset = pos + self.pattern.chars().count();
-                        offset..usize::MAX
-                    }
-                    KeeperCutoff::Before => usize::MIN..pos,
-                },
-            },
-        };
+impl KeeperMatch {
+    fn resolution(self, span: impl Fn(std::ops::Range<usize>) -> KeeperSpan) -> KeeperResolution {
+        KeeperResolution {
+            kept: span(self.kept),
+            pattern: span(self.pattern),
+            until_pattern: self.until_pattern.map(span),
+        }
+    }
+}
 
-        write!(f, "{}", self.to_parse.substring(range))
+fn checked_range(text: &str, range: std::ops::Range<usize>) -> Result<std::ops::Range<usize>, KeeperError> {
+    if range.start <= range.end
+        && range.end <= text.len()
+        && text.is_char_boundary(range.start)
+        && text.is_char_boundary(range.end) {
+        Ok(range)
+    } else {
+        Err(KeeperError::InvalidRange { start: range.start, end: range.end })
     }
 }
 
-impl std::fmt::Display for StringKeeper<char, String> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let try_find = match self.opt.period {
-            KeeperPeriod::Start => self.to_parse.find(self.pattern),
-            KeeperPeriod::End => self.to_parse.rfind(self.pattern),
+struct KeeperOccurrences<'k, 's, T> {
+    spec: &'k KeeperSpec<T>,
+    pattern: Matcher<'k, T>,
+    until_pattern: Option<Matcher<'k, T>>,
+    to_parse: &'s str,
+    cursor: Option<usize>,
+}
+
+impl<T: KeeperPattern> Iterator for KeeperOccurrences<'_, '_, T> {
+    type Item = Result<KeeperMatch, KeeperError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let to_parse = self.to_parse;
+        let cursor = self.cursor.take()?;
+        let matched = match self.spec.opt.period {
+            KeeperPeriod::Start => self.pattern.next_match(to_parse, cursor),
+            KeeperPeriod::End => self.pattern.next_match_back(to_parse, cursor),
+        }?;
+        let matched = match checked_range(to_parse, matched) {
+            Ok(matched) => matched,
+            Err(err) => return Some(Err(err)),
         };
+        let found = self.spec.select(to_parse, matched.clone(), self.until_pattern.as_ref());
+
+        // continue past the (possibly widened) match; empty matches step over one char
+        let matched = found.as_ref().map_or(matched, |found| found.pattern.clone());
+        self.cursor = match self.spec.opt.period {
+            KeeperPeriod::Start if matched.is_empty() => to_parse[matched.end..]
+                .chars()
+                .next()
+                .map(|c| matched.end + c.len_utf8()),
+            KeeperPeriod::Start => Some(matched.end),
+            KeeperPeriod::End if matched.is_empty() => to_parse[..matched.start]
+                .chars()
+                .next_back()
+                .map(|c| matched.start - c.len_utf8()),
+            KeeperPeriod::End => Some(matched.start),
+        };
+        Some(found)
+    }
+}
 
-        let range = match try_find {
-            None => usize::MIN..usize::MIN,
-            Some(pos) => match self.opt.clusivity {
-                KeeperClusivity::Including => match self.opt.cutoff {
-                    KeeperCutoff::After => pos..usize::MAX,
-                    KeeperCutoff::Before => usize::MIN..(pos).saturating_add(1),
-                },
-                KeeperClusivity::Excluding => match self.opt.cutoff {
-                    KeeperCutoff::After => (pos).saturating_add(1)..usize::MAX,
-                    KeeperCutoff::Before => usize::MIN..pos,
-                },
-            },
+impl<T: KeeperPattern> KeeperSpec<T> {
+    fn occurrences<'k, 's>(&'k self, to_parse: &'s str) -> KeeperOccurrences<'k, 's, T> {
+        let cursor = match self.opt.period {
+            KeeperPeriod::Start => usize::MIN,
+            KeeperPeriod::End => to_parse.len(),
         };
+        KeeperOccurrences {
+            spec: self,
+            pattern: Matcher::new(&self.pattern, &self.opt.matching, to_parse),
+            until_pattern: self
+                .until_pattern
+                .as_ref()
+                .map(|until_pattern| Matcher::new(until_pattern, &self.opt.matching, to_parse)),
+            to_parse,
+            cursor: Some(cursor),
+        }
+    }
 
-        let mut result = self.to_parse.substring(range);
+    fn locate(&self, to_parse: &str) -> Result<KeeperMatch, KeeperError> {
+        self
+            .occurrences(to_parse)
+            .nth(self.opt.occurrence)
+            .unwrap_or(Err(KeeperError::PatternNotFound))
+    }
 
-        let opt_range = if let Some(until_pattern) = self.until_pattern {
-            if let Some(until_match) = self.opt.until_match.clone() {
-                let try_find = match self.opt.period {
-                    KeeperPeriod::Start => result.find(until_pattern),
-                    KeeperPeriod::End => result.rfind(until_pattern),
+    fn select(
+        &self,
+        to_parse: &str,
+        matched: std::ops::Range<usize>,
+        until_pattern: Option<&impl KeeperPattern>,
+    ) -> Result<KeeperMatch, KeeperError> {
+        let (matched, bound) = match (until_pattern, &self.opt.until_match) {
+            (Some(until_pattern), Some(KeeperUntilMatch::FirstMatch)) => {
+                let bound = match self.opt.cutoff {
+                    KeeperCutoff::After => until_pattern.next_match(to_parse, matched.end),
+                    KeeperCutoff::Before => until_pattern.next_match_back(to_parse, matched.start),
+                }.ok_or(KeeperError::UntilPatternNotFound)?;
+                let bound = checked_range(to_parse, bound)?;
+                let beyond = match self.opt.cutoff {
+                    KeeperCutoff::After => bound.start >= matched.end,
+                    KeeperCutoff::Before => bound.end <= matched.start,
                 };
-
-                if let Some(pos) = try_find {
-                    match until_match {
-                        KeeperUntilMatch::FirstMatch => {
-                            match self.opt.cutoff {
-                                KeeperCutoff::After => {
-                                    result
-                                        .substring(pos..)
-                                        .find(until_pattern)
-                                        .map(|start_idx| start_idx..usize::MAX)
-                                }
-                                KeeperCutoff::Before => {
-                                    result
-                                        .substring(..=pos)
-                                        .rfind(until_pattern)
-                                        .map(|end_idx| pos..end_idx)
-                                }
-                            }
-                        }
-                        KeeperUntilMatch::NoMatch => {
-                            match self.opt.cutoff {
-                                KeeperCutoff::After => {
-                                    let found = {
-                                        let mut flag = false;
-                                        result
-                                            .chars()
-                                            .enumerate()
-                                            .skip(pos)
-                                            .find(|(c_idx, c)| {
-                                                if *c_idx > pos {
-                                                    let same = *c == until_pattern;
-                                                    if flag && !same {
-                                                        return true;
-                                                    }
-
-                                                    if same {
-                                                        flag = true;
-                                                    }
-                                                }
-
-                                                false
-                                            })
-                                    };
-
-                                    if let Some((start_pos, _)) = found {
-                                        Some(start_pos.saturating_add(1)..pos.saturating_add(1))
-                                    } else {
-                                        None
-                                    }
-                                }
-                                KeeperCutoff::Before => {
-                                    let col = result
-                                        .chars()
-                                        .enumerate()
-                                        .collect::<Vec<(usize, char)>>();
-
-                                    let found = {
-                                        let mut flag = false;
-                                        col
-                                            .iter()
-                                            .rev()
-                                            .find(|(c_idx, c)| {
-                                                if *c_idx <= pos {
-                                                    let same = *c == until_pattern;
-                                                    if flag && !same {
-                                                        return true;
-                                                    }
-
-                                                    if same {
-                                                        flag = true;
-                                                    }
-                                                }
-
-                                                false
-                                            })
-                                    };
-
-                                    if let Some(&(start_pos, _)) = found {
-                                        Some(start_pos.saturating_add(1)..pos.saturating_add(1))
-                                    } else {
-                                        None
-                                    }
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    None
+                if !beyond {
+                    return Err(KeeperError::InvalidRange { start: bound.start, end: bound.end });
                 }
-            } else {
-                None
+                (matched, Some(bound))
             }
-        } else {
-            None
-        };
-
-        let result = if let Some(range) = opt_range {
-            match self.opt.mode {
-                StringKeeperMode::Cut => {
-                    result.replace_range(range, "");
-                    result
+            (Some(until_pattern), Some(KeeperUntilMatch::NoMatch)) => {
+                let mut matched = matched;
+                while let Some(start) = until_pattern
+                    .match_back_at(to_parse, matched.start)
+                    .filter(|start| *start < matched.start) {
+                    matched = checked_range(to_parse, start..matched.end)?;
                 }
-                StringKeeperMode::Keep => {
-                    result.substring(range)
+                while let Some(end) = until_pattern
+                    .match_at(to_parse, matched.end)
+                    .filter(|end| *end > matched.end) {
+                    matched = checked_range(to_parse, matched.start..end)?;
                 }
+                (matched, None)
             }
-        } else {
-            result
+            _ => (matched, None),
         };
-        write!(f, "{}", result)
-    }
-}
 
-#[cfg(feature = "regex")]
-impl std::fmt::Display for StringKeeper<regex::Regex, String> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let to_parse = self.to_parse.as_str();
-        let try_find = match self.opt.period {
-            KeeperPeriod::Start => {
-                self.pattern.find(to_parse)
-            }
-            KeeperPeriod::End => {
-                self.pattern.find_iter(to_parse).last()
-            }
+        let kept = match self.opt.clusivity {
+            KeeperClusivity::Including => match self.opt.cutoff {
+                KeeperCutoff::After => matched.start..bound.as_ref().map_or(to_parse.len(), |bound| bound.end),
+                KeeperCutoff::Before => bound.as_ref().map_or(usize::MIN, |bound| bound.start)..matched.end,
+            },
+            KeeperClusivity::Excluding => match self.opt.cutoff {
+                KeeperCutoff::After => matched.end..bound.as_ref().map_or(to_parse.len(), |bound| bound.start),
+                KeeperCutoff::Before => bound.as_ref().map_or(usize::MIN, |bound| bound.end)..matched.start,
+            },
         };
 
-        let range = match try_find {
-            None => usize::MIN..usize::MIN,
-            Some(pos) => match self.opt.clusivity {
-                KeeperClusivity::Including => match self.opt.cutoff {
-                    KeeperCutoff::After => pos.start()..usize::MAX,
-                    KeeperCutoff::Before => {
-                        let end_offset = if let Some(enc) = self.opt.encoding.clone() {
-                            let matched_string = pos.as_str();
-                            let char_len = matched_string
-                                .chars()
-                                .last()
-                                .map(|last_char| {
-                                    match enc {
-                                        KeeperEncoding::Utf8 => char::len_utf8(last_char),
-                                        KeeperEncoding::Utf16 => char::len_utf16(last_char),
-                                    }
-                                })
-                                .unwrap_or(std::mem::size_of::<char>());
-                            pos.end().saturating_sub(char_len)
-                        } else {
-                            pos.end()
-                        };
-                        usize::MIN..end_offset
-                    }
-                },
-                KeeperClusivity::Excluding => match self.opt.cutoff {
-                    KeeperCutoff::After => {
-                        let offset = pos.as_str().chars().count();
-                        let start = pos.start() + offset;
-                        start..usize::MAX
-                    }
-                    KeeperCutoff::Before => usize::MIN..pos.start(),
-                },
+        Ok(KeeperMatch { kept, pattern: matched, until_pattern: bound })
+    }
+
+    /// The byte ranges of the rendered head and tail; the tail is only non-empty in cut mode.
+    fn kept_ranges(
+        &self,
+        to_parse: &str,
+    ) -> Result<(std::ops::Range<usize>, std::ops::Range<usize>), KeeperError> {
+        let end = to_parse.len();
+        match self.locate(to_parse) {
+            Ok(found) => Ok(self.opt.mode.split_ranges(end, found.kept)),
+            Err(err) => match self.opt.fallback {
+                KeeperFallback::Empty => Ok((usize::MIN..usize::MIN, end..end)),
+                KeeperFallback::WholeInput => Ok((usize::MIN..end, end..end)),
+                KeeperFallback::Error => Err(err),
             },
-        };
+        }
+    }
+
+    fn kept_parts<'s>(&self, to_parse: &'s str) -> Result<(&'s str, &'s str), KeeperError> {
+        self
+            .kept_ranges(to_parse)
+            .map(|(head, tail)| (&to_parse[head], &to_parse[tail]))
+    }
+
+    /// Runs the rule over `to_parse`, like `to_parse.keep(..)` configured the same way.
+    pub fn apply<'a>(&self, to_parse: &'a str) -> std::borrow::Cow<'a, str> {
+        self.try_apply(to_parse).unwrap_or_default()
+    }
+
+    pub fn try_apply<'a>(&self, to_parse: &'a str) -> Result<std::borrow::Cow<'a, str>, KeeperError> {
+        self.kept_parts(to_parse).map(join_kept)
+    }
+
+    pub fn resolve(&self, to_parse: &str) -> Option<KeeperResolution> {
+        self.try_resolve(to_parse).ok()
+    }
+
+    pub fn try_resolve(&self, to_parse: &str) -> Result<KeeperResolution, KeeperError> {
+        self.resolve_with(to_parse, |bytes, utf16| KeeperSpan::new(to_parse, bytes, utf16))
+    }
+
+    /// Like [`try_resolve`](KeeperSpec::try_resolve), with a custom conversion of byte ranges;
+    /// the flag tells whether UTF-16 ranges were requested.
+    pub(crate) fn resolve_with(
+        &self,
+        to_parse: &str,
+        span: impl Fn(std::ops::Range<usize>, bool) -> KeeperSpan,
+    ) -> Result<KeeperResolution, KeeperError> {
+        let utf16 = self.opt.encoding == Some(KeeperEncoding::Utf16);
+        self
+            .locate(to_parse)
+            .map(|found| found.resolution(|bytes| span(bytes, utf16)))
+    }
+}
 
-        write!(f, "{}", self.to_parse.substring(range))
+impl<T: KeeperPattern, P: AsRef<str>> StringKeeper<T, P> {
+    /// Locates the selected region and the matched pattern in the input, or `None` when the
+    /// pattern (or a required until pattern) is absent. UTF-16 ranges are only reported with
+    /// [`utf16_encoding`](KeeperCommonExt::utf16_encoding).
+    pub fn resolve(&self) -> Option<KeeperResolution> {
+        self.try_resolve().ok()
+    }
+
+    pub fn try_resolve(&self) -> Result<KeeperResolution, KeeperError> {
+        self.spec.try_resolve(self.to_parse.as_ref())
+    }
+
+    /// The rendered result, borrowed from the input whenever it is a single contiguous slice.
+    /// With [`KeeperFallback::Error`] a missing pattern renders as an empty string.
+    pub fn as_cow(&self) -> std::borrow::Cow<'_, str> {
+        self.try_as_cow().unwrap_or_default()
+    }
+
+    pub fn try_as_cow(&self) -> Result<std::borrow::Cow<'_, str>, KeeperError> {
+        self.spec.kept_parts(self.to_parse.as_ref()).map(join_kept)
+    }
+
+    /// Renders the result, applying the configured [`KeeperFallback`] when the pattern, the
+    /// until pattern or a reported match range is unusable.
+    pub fn try_to_string(&self) -> Result<String, KeeperError> {
+        self.try_as_cow().map(std::borrow::Cow::into_owned)
+    }
+
+    /// Every result the keeper can render, one per match of the pattern, starting at the
+    /// [`nth_occurrence`](KeeperCommonExt::nth_occurrence). Matches without a usable until
+    /// pattern are skipped.
+    pub fn segments(&self) -> impl Iterator<Item = std::borrow::Cow<'_, str>> + '_ {
+        let to_parse = self.to_parse.as_ref();
+        self
+            .spec
+            .occurrences(to_parse)
+            .skip(self.spec.opt.occurrence)
+            .filter_map(Result::ok)
+            .map(move |found| join_kept(self.spec.opt.mode.split(to_parse, found.kept)))
+    }
+
+    /// Like [`segments`](StringKeeper::segments), but reports where each region was found.
+    /// Matches without a usable until pattern or match range yield the error
+    /// [`try_resolve`](StringKeeper::try_resolve) would report instead of being skipped.
+    ///
+    /// Offsets are converted through an [`IndexedStr`] built on the first match, so resolving
+    /// every match stays linear in the input length.
+    pub fn resolve_all(&self) -> impl Iterator<Item = Result<KeeperResolution, KeeperError>> + '_ {
+        let to_parse = self.to_parse.as_ref();
+        let utf16 = self.spec.opt.encoding == Some(KeeperEncoding::Utf16);
+        let mut index = None;
+        self
+            .spec
+            .occurrences(to_parse)
+            .skip(self.spec.opt.occurrence)
+            .map(move |found| {
+                let found = found?;
+                let index = index.get_or_insert_with(|| match IndexedStr::new(to_parse) {
+                    index if utf16 => index.with_utf16(),
+                    index => index,
+                });
+                Ok(found.resolution(|bytes| {
+                    index
+                        .span(bytes.clone())
+                        .unwrap_or_else(|| KeeperSpan::new(to_parse, bytes, utf16))
+                }))
+            })
+    }
+}
+
+impl<'a, T: KeeperPattern> StringKeeper<T, &'a str> {
+    /// Like [`as_cow`](StringKeeper::as_cow), but borrows from the original `&'a str`.
+    pub fn into_cow(self) -> std::borrow::Cow<'a, str> {
+        self.try_into_cow().unwrap_or_default()
+    }
+
+    pub fn try_into_cow(self) -> Result<std::borrow::Cow<'a, str>, KeeperError> {
+        self.spec.kept_parts(self.to_parse).map(join_kept)
+    }
+}
+
+impl StringKeeperMode {
+    fn split<'s>(&self, to_parse: &'s str, range: std::ops::Range<usize>) -> (&'s str, &'s str) {
+        let (head, tail) = self.split_ranges(to_parse.len(), range);
+        (&to_parse[head], &to_parse[tail])
+    }
+
+    fn split_ranges(
+        &self,
+        end: usize,
+        range: std::ops::Range<usize>,
+    ) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        match self {
+            StringKeeperMode::Keep => (range, end..end),
+            StringKeeperMode::Cut => (usize::MIN..range.start, range.end..end),
+        }
+    }
+}
+
+fn join_kept<'s>((head, tail): (&'s str, &'s str)) -> std::borrow::Cow<'s, str> {
+    if tail.is_empty() {
+        std::borrow::Cow::Borrowed(head)
+    } else if head.is_empty() {
+        std::borrow::Cow::Borrowed(tail)
+    } else {
+        std::borrow::Cow::Owned([head, tail].concat())
+    }
+}
+
+impl<T: KeeperPattern, P: AsRef<str>> std::fmt::Display for StringKeeper<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_cow())
     }
 }
 
 impl SubstringExt for str {
     fn substring<R: std::ops::RangeBounds<usize>>(&self, range: R) -> String {
-        self.try_substring(range).unwrap_or_else(|| "".to_string())
+        self.try_substring(range).unwrap_or_default()
     }
 
     fn substring_len(&self, reverse_count: usize) -> String {
-        self.substring(self.len().saturating_sub(reverse_count)..)
+        self.substring(self.chars().count().saturating_sub(reverse_count)..)
     }
 
     fn try_substring<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<String> {
-        let start_idx = match range.start_bound() {
-            std::collections::Bound::Included(v) => *v,
-            std::collections::Bound::Excluded(v) => v.saturating_add(1),
-            std::collections::Bound::Unbounded => usize::MIN,
+        self.try_substr(range).map(str::to_string)
+    }
+
+    fn trim_trailing_zeros(&self) -> String {
+        self.trim_run_end('0', usize::MAX).to_string()
+    }
+
+    fn substr<R: std::ops::RangeBounds<usize>>(&self, range: R) -> &str {
+        self.try_substr(range).unwrap_or_default()
+    }
+
+    fn trim_run_start<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str {
+        &self[run_start(self, &pattern, limit)..]
+    }
+
+    fn trim_run_end<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str {
+        &self[..run_end(self, &pattern, limit)]
+    }
+
+    fn trim_run_both<T: KeeperPattern>(&self, pattern: T, limit: usize) -> &str {
+        let trimmed = &self[run_start(self, &pattern, limit)..];
+        &trimmed[..run_end(trimmed, &pattern, limit)]
+    }
+
+    fn try_substr<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<&str> {
+        let (start_idx, take_count) = take_bounds(range)?;
+        let boundaries = self.char_indices().map(|(pos, _)| pos);
+        Some(&self[unit_range(boundaries, self.len(), start_idx, take_count)])
+    }
+
+    fn slice<R: std::ops::RangeBounds<isize>>(&self, range: R, step: isize) -> String {
+        self.try_slice(range, step).unwrap_or_default()
+    }
+
+    fn try_slice<R: std::ops::RangeBounds<isize>>(&self, range: R, step: isize) -> Option<String> {
+        if step == 0 {
+            return None;
+        }
+
+        let chars = self.chars().collect::<Vec<char>>();
+        let len = chars.len() as isize;
+        let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+        let absolute = |idx: isize, shift: isize| {
+            let idx = if idx < 0 { idx.saturating_add(len) } else { idx };
+            idx.saturating_add(shift).clamp(lower, upper)
+        };
+
+        let start = match range.start_bound() {
+            std::collections::Bound::Included(v) => absolute(*v, 0),
+            std::collections::Bound::Excluded(v) => absolute(*v, step.signum()),
+            std::collections::Bound::Unbounded => if step > 0 { lower } else { upper },
         };
 
-        let end_idx = match range.end_bound() {
-            std::collections::Bound::Included(v) => v.saturating_add(1),
-            std::collections::Bound::Excluded(v) => *v,
-            std::collections::Bound::Unbounded => usize::MAX,
+        let stop = match range.end_bound() {
+            std::collections::Bound::Included(v) => absolute(*v, step.signum()),
+            std::collections::Bound::Excluded(v) => absolute(*v, 0),
+            std::collections::Bound::Unbounded => if step > 0 { upper } else { lower },
         };
 
-        if end_idx > start_idx {
-            end_idx
-                .checked_sub(start_idx)
-                .map(|take_count| {
-                    self
-                        .chars()
-                        .skip(start_idx)
-                        .take(take_count)
-                        .collect()
-                })
-        } else {
-            None
+        let mut result = String::new();
+        let mut idx = start;
+        while (step > 0 && idx < stop) || (step < 0 && idx > stop) {
+            result.push(chars[idx as usize]);
+            idx = idx.saturating_add(step);
         }
+
+        Some(result)
     }
 
-    fn trim_trailing_zeros(&self) -> String {
-        self
-            .to_string()
-            .cut('0')
-            .end_of_string()
-            .until_no_matched_pattern('0')
-            .before_pattern()
-            .excluding_pattern()
-            .to_string()
+    fn substring_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> String {
+        self.try_substring_bytes(range, policy).unwrap_or_default()
+    }
+
+    fn try_substring_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<String> {
+        self.try_substr_bytes(range, policy).map(str::to_string)
+    }
+
+    fn substr_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> &str {
+        self.try_substr_bytes(range, policy).unwrap_or_default()
+    }
+
+    fn try_substr_bytes<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<&str> {
+        let (start_idx, take_count) = take_bounds(range)?;
+        let locate = |idx: usize| {
+            let idx = idx.min(self.len());
+            if self.is_char_boundary(idx) {
+                Ok(idx)
+            } else {
+                let start = (usize::MIN..idx).rev().find(|pos| self.is_char_boundary(*pos)).unwrap_or_default();
+                let end = (idx..self.len()).find(|pos| self.is_char_boundary(*pos)).unwrap_or(self.len());
+                Err(start..end)
+            }
+        };
+
+        let start = policy.snap(locate(start_idx), true)?;
+        let end = policy.snap(locate(start_idx.saturating_add(take_count)), false)?;
+        Some(&self[start..end.max(start)])
+    }
+
+    fn substring_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> String {
+        self.try_substring_utf16(range, policy).unwrap_or_default()
+    }
+
+    fn try_substring_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<String> {
+        self.try_substr_utf16(range, policy).map(str::to_string)
+    }
+
+    fn substr_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> &str {
+        self.try_substr_utf16(range, policy).unwrap_or_default()
+    }
+
+    fn try_substr_utf16<R: std::ops::RangeBounds<usize>>(&self, range: R, policy: BoundaryPolicy) -> Option<&str> {
+        let (start_idx, take_count) = take_bounds(range)?;
+        let locate = |idx: usize| {
+            let mut units = usize::MIN;
+            for (pos, c) in self.char_indices() {
+                if units >= idx {
+                    return Ok(pos);
+                }
+                units += c.len_utf16();
+                if units > idx {
+                    return Err(pos..pos + c.len_utf8());
+                }
+            }
+            Ok(self.len())
+        };
+
+        let start = policy.snap(locate(start_idx), true)?;
+        let end = policy.snap(locate(start_idx.saturating_add(take_count)), false)?;
+        Some(&self[start..end.max(start)])
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn substring_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> String {
+        self.try_substring_graphemes(range).unwrap_or_default()
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn substring_len_graphemes(&self, reverse_count: usize) -> String {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let count = self.graphemes(true).count();
+        self.substring_graphemes(count.saturating_sub(reverse_count)..)
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn try_substring_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<String> {
+        self.try_substr_graphemes(range).map(str::to_string)
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn substr_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> &str {
+        self.try_substr_graphemes(range).unwrap_or_default()
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    fn try_substr_graphemes<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Option<&str> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let (start_idx, take_count) = take_bounds(range)?;
+        let boundaries = self.grapheme_indices(true).map(|(pos, _)| pos);
+        Some(&self[unit_range(boundaries, self.len(), start_idx, take_count)])
+    }
+}
+
+/// The end of a run of up to `limit` back-to-back matches at the start of `text`.
+fn run_start(text: &str, pattern: &impl KeeperPattern, limit: usize) -> usize {
+    let mut start = usize::MIN;
+    for _ in 0..limit {
+        match pattern
+            .match_at(text, start)
+            .filter(|end| *end > start && text.is_char_boundary(*end)) {
+            Some(end) => start = end,
+            None => break,
+        }
+    }
+    start
+}
+
+/// The start of a run of up to `limit` back-to-back matches at the end of `text`.
+fn run_end(text: &str, pattern: &impl KeeperPattern, limit: usize) -> usize {
+    let mut end = text.len();
+    for _ in 0..limit {
+        match pattern
+            .match_back_at(text, end)
+            .filter(|start| *start < end && text.is_char_boundary(*start)) {
+            Some(start) => end = start,
+            None => break,
+        }
+    }
+    end
+}
+
+impl BoundaryPolicy {
+    fn snap(&self, located: Result<usize, std::ops::Range<usize>>, is_start: bool) -> Option<usize> {
+        match (located, self) {
+            (Ok(pos), _) => Some(pos),
+            (Err(_), BoundaryPolicy::Reject) => None,
+            (Err(code_point), BoundaryPolicy::Shrink) => Some(if is_start { code_point.end } else { code_point.start }),
+            (Err(code_point), BoundaryPolicy::Expand) => Some(if is_start { code_point.start } else { code_point.end }),
+        }
+    }
+}
+
+fn unit_range(
+    mut boundaries: impl Iterator<Item = usize>,
+    len: usize,
+    start_idx: usize,
+    take_count: usize,
+) -> std::ops::Range<usize> {
+    let start = boundaries.nth(start_idx).unwrap_or(len);
+    let end = match take_count.checked_sub(1) {
+        Some(skip) => boundaries.nth(skip).unwrap_or(len),
+        None => start,
+    };
+    start..end.max(start)
+}
+
+fn take_bounds<R: std::ops::RangeBounds<usize>>(range: R) -> Option<(usize, usize)> {
+    let start_idx = match range.start_bound() {
+        std::collections::Bound::Included(v) => *v,
+        std::collections::Bound::Excluded(v) => v.saturating_add(1),
+        std::collections::Bound::Unbounded => usize::MIN,
+    };
+
+    let end_idx = match range.end_bound() {
+        std::collections::Bound::Included(v) => v.saturating_add(1),
+        std::collections::Bound::Excluded(v) => *v,
+        std::collections::Bound::Unbounded => usize::MAX,
+    };
+
+    if end_idx > start_idx {
+        end_idx
+            .checked_sub(start_idx)
+            .map(|take_count| (start_idx, take_count))
+    } else {
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::prelude::*;
+    use super::BoundaryPolicy;
 
     #[test]
     fn try_substring() {
@@ -521,6 +1081,28 @@ mod tests {
         assert_eq!("fõøbα®".substring(2..5), "øbα");
     }
 
+    #[test]
+    fn test_substring_bytes() {
+        assert_eq!("fõøbα®".substring_bytes(3..8, BoundaryPolicy::Reject), "øbα");
+        assert_eq!("fõøbα®".try_substring_bytes(2..8, BoundaryPolicy::Reject), None);
+        assert_eq!("fõøbα®".substring_bytes(2..7, BoundaryPolicy::Shrink), "øb");
+        assert_eq!("fõøbα®".substring_bytes(2..7, BoundaryPolicy::Expand), "õøbα");
+        assert_eq!("fõøbα®".substring_bytes(3.., BoundaryPolicy::Reject), "øbα®");
+        assert_eq!("fõøbα®".substring_bytes(..100, BoundaryPolicy::Reject), "fõøbα®");
+        assert_eq!("ø".try_substring_bytes(1..2, BoundaryPolicy::Shrink), Some("".to_string()));
+        assert_eq!("ø".try_substring_bytes(1..1, BoundaryPolicy::Expand), None);
+    }
+
+    #[test]
+    fn test_substring_utf16() {
+        assert_eq!("a𝄞bα".substring_utf16(1..3, BoundaryPolicy::Reject), "𝄞");
+        assert_eq!("a𝄞bα".try_substring_utf16(2..4, BoundaryPolicy::Reject), None);
+        assert_eq!("a𝄞bα".substring_utf16(2..5, BoundaryPolicy::Shrink), "bα");
+        assert_eq!("a𝄞bα".substring_utf16(..2, BoundaryPolicy::Expand), "a𝄞");
+        assert_eq!("a𝄞bα".substring_utf16(3.., BoundaryPolicy::Reject), "bα");
+        assert_eq!("a𝄞bα".substring_utf16(10.., BoundaryPolicy::Reject), "");
+    }
+
     #[test]
     fn mozilla_substring_cases() {
         let any_string = "Mozilla";
@@ -540,6 +1122,56 @@ mod tests {
         assert_eq!(any_string.substring(..), "Mozilla");
     }
 
+    #[test]
+    fn test_substr_borrows() {
+        let some_text = "42Hello, wørld!";
+        let result = some_text.substr(9..14);
+        assert_eq!(result, "wørld");
+        assert!(std::ptr::eq(result.as_ptr(), some_text[9..].as_ptr()));
+        assert_eq!(some_text.try_substr(3..3), None);
+        assert_eq!(some_text.try_substr(20..), Some(""));
+        assert_eq!(some_text.substr_bytes(10..12, BoundaryPolicy::Reject), "ø");
+        assert_eq!(some_text.substr_utf16(..2, BoundaryPolicy::Reject), "42");
+    }
+
+    #[test]
+    fn python_slice_cases() {
+        use std::ops::Bound;
+
+        // Ported from CPython's Lib/test/test_slice.py `test_indices`, applied to a 10 char string.
+        let digits = "0123456789";
+        assert_eq!(digits.slice(.., 1), "0123456789");
+        assert_eq!(digits.slice(.., 2), "02468");
+        assert_eq!(digits.slice(1.., 2), "13579");
+        assert_eq!(digits.slice(.., -1), "9876543210");
+        assert_eq!(digits.slice(.., -2), "97531");
+        assert_eq!(digits.slice(3.., -2), "31");
+        assert_eq!(digits.slice(..-9, 1), "0");
+        assert_eq!(digits.slice(..-10, 1), "");
+        assert_eq!(digits.slice(..-11, 1), "");
+        assert_eq!(digits.slice(..-10, -1), "987654321");
+        assert_eq!(digits.slice(..-11, -1), "9876543210");
+        assert_eq!(digits.slice(..-12, -1), "9876543210");
+        assert_eq!(digits.slice(..9, 1), "012345678");
+        assert_eq!(digits.slice(..10, 1), "0123456789");
+        assert_eq!(digits.slice(..11, 1), "0123456789");
+        assert_eq!(digits.slice(..8, -1), "9");
+        assert_eq!(digits.slice(..9, -1), "");
+        assert_eq!(digits.slice(..10, -1), "");
+        assert_eq!(digits.slice(-100..100, 1), digits.slice(.., 1));
+        assert_eq!(digits.slice((Bound::Included(100), Bound::Excluded(-100)), -1), digits.slice(.., -1));
+        assert_eq!(digits.slice(-100..100, 2), "02468");
+        assert_eq!(digits.try_slice(.., 0), None);
+
+        let any_string = "karøbα";
+        assert_eq!(any_string.slice(-4.., 1), "røbα");
+        assert_eq!(any_string.slice(..-1, 1), "karøb");
+        assert_eq!(any_string.slice(..=-1, 1), "karøbα");
+        assert_eq!(any_string.slice(..=0, -1), "αbørak");
+        assert_eq!(any_string.slice(-2..=1, -1), "børa");
+        assert_eq!(any_string.substring_len(4), "røbα");
+    }
+
     #[test]
     fn test_keep_after_include_string() {
         assert_eq!(
@@ -739,8 +1371,218 @@ mod tests {
 }
 
 #[cfg(test)]
-#[cfg(feature = "regex")]
-mod regex_feature_tests {
+mod cut_tests {
+    use super::prelude::*;
+
+    #[test]
+    fn test_cut_after_include_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut("karøbα".to_string())
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            "this is "
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut("kar".to_string())
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_cut_after_exclude_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut("karøbα".to_string())
+                .beginning_of_string()
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "this is karøbα"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut("kar".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "kar"
+        );
+    }
+
+    #[test]
+    fn test_cut_before_include_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut("øbα".to_string())
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            " it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut("øbα".to_string())
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_cut_before_exclude_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut("øbα".to_string())
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut("øbα".to_string())
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα"
+        );
+    }
+
+    #[test]
+    fn test_cut_missing_pattern() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut("missing".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "this is karøbα it was"
+        );
+    }
+
+    #[test]
+    fn test_cut_after_include_char() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut('k')
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            "this is "
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut('k')
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_cut_after_exclude_char() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut('k')
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "this is k"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut('k')
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "k"
+        );
+    }
+
+    #[test]
+    fn test_cut_before_include_char() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut('ø')
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            "bα it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut('ø')
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            "bα"
+        );
+    }
+
+    #[test]
+    fn test_cut_before_exclude_char() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut('ø')
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut('ø')
+                .end_of_string()
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα"
+        );
+    }
+
+    #[test]
+    fn test_cut_until_first_matched() {
+        assert_eq!(
+            "head BEGIN karøbα END tail"
+                .to_string()
+                .cut("BEGIN".to_string())
+                .until_first_matched_pattern("END".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "head BEGINEND tail"
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "regex")]
+mod regex_feature_tests {
     use super::prelude::*;
     use regex::Regex;
 
@@ -862,30 +1704,793 @@ mod regex_feature_tests {
             "kar"
         );
     }
-}
-
-#[cfg(test)]
-mod pattern_keep_until {
-    use crate::prelude::*;
 
     #[test]
-    fn to_keep_until_pattern() {
+    fn test_keep_until_first_matched() {
         assert_eq!(
-            "42.141592650991234200000000000000000000"
+            "id: 42 name: karøbα age: 7"
                 .to_string()
-                .cut('0')
-                .end_of_string()
-                .until_no_matched_pattern('0')
+                .keep(Regex::new(r"name:\s*").unwrap())
+                .until_first_matched_pattern(Regex::new(r"\s+\w+:").unwrap())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα"
+        );
+        assert_eq!(
+            "id: 42 name: karøbα age: 7"
+                .to_string()
+                .keep(Regex::new(r"age").unwrap())
+                .until_first_matched_pattern(Regex::new(r"\d+").unwrap())
                 .before_pattern()
+                .including_pattern()
+                .to_string(),
+            "42 name: karøbα age"
+        );
+    }
+
+    #[test]
+    fn test_keep_until_no_matched() {
+        assert_eq!(
+            "total = 0042"
+                .to_string()
+                .keep(Regex::new("=").unwrap())
+                .until_no_matched_pattern(Regex::new(r"[ 0]").unwrap())
+                .after_pattern()
                 .excluding_pattern()
                 .to_string(),
-            "42.1415926509912342"
+            "42"
         );
+    }
 
+    #[test]
+    fn test_cut_after_include_string() {
         assert_eq!(
-            "42.141592650991234200000000000000000000".trim_trailing_zeros(),
-            "42.1415926509912342"
+            "this is karøbα it was"
+                .to_string()
+                .cut(Regex::new("karøbα").unwrap())
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            "this is "
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut(Regex::new("kar").unwrap())
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_cut_after_exclude_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut(Regex::new("karøbα").unwrap())
+                .beginning_of_string()
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "this is karøbα"
         );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut(Regex::new("kar").unwrap())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "kar"
+        );
+    }
 
+    #[test]
+    fn test_cut_before_include_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut(Regex::new("øbα").unwrap())
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            " it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut(Regex::new("øbα").unwrap())
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            ""
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cut_before_exclude_string() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut(Regex::new("øbα").unwrap())
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα it was"
+        );
+        assert_eq!(
+            "karøbα"
+                .to_string()
+                .cut(Regex::new("øbα").unwrap())
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "øbα"
+        );
+    }
+
+    #[test]
+    fn test_cut_missing_pattern() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .cut(Regex::new("missing").unwrap())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "this is karøbα it was"
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "unicode-segmentation")]
+mod grapheme_feature_tests {
+    use super::prelude::*;
+
+    #[test]
+    fn test_multiple_byte_characters() {
+        assert_eq!("ã".substring_graphemes(..1), "ã");
+        assert_eq!("ã".substring_graphemes(1..2), "");
+        assert_eq!("fõøbα®".substring_graphemes(2..5), "øbα");
+        assert_eq!("👩‍👩‍👧 family".substring_graphemes(..=0), "👩‍👩‍👧");
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        assert_eq!("ãõ".substring_graphemes(..10), "ãõ");
+        assert_eq!("ãõ".try_substring_graphemes(2..10), Some("".to_string()));
+        assert_eq!("ãõ".try_substring_graphemes(1..1), None);
+    }
+
+    #[test]
+    fn test_substr_graphemes() {
+        let text = "kar🇳🇴ã";
+        assert_eq!(text.substr_graphemes(3..4), "🇳🇴");
+        assert_eq!(text.try_substr_graphemes(4..4), None);
+    }
+
+    #[test]
+    fn test_substring_len() {
+        assert_eq!("kar🇳🇴ã".substring_len_graphemes(2), "🇳🇴ã");
+        assert_eq!("kar🇳🇴ã".substring_len_graphemes(10), "kar🇳🇴ã");
+    }
+}
+
+#[cfg(test)]
+mod pattern_keep_until {
+    use crate::prelude::*;
+
+    #[test]
+    fn to_keep_until_pattern() {
+        assert_eq!(
+            "42.141592650991234200000000000000000000"
+                .to_string()
+                .cut('0')
+                .end_of_string()
+                .until_no_matched_pattern('0')
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            "42.1415926509912342"
+        );
+
+        assert_eq!(
+            "42.141592650991234200000000000000000000"
+                .to_string()
+                .keep('0')
+                .end_of_string()
+                .until_no_matched_pattern('0')
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "42.1415926509912342"
+        );
+
+        assert_eq!(
+            "42.141592650991234200000000000000000000".trim_trailing_zeros(),
+            "42.1415926509912342"
+        );
+        assert_eq!("1.05".trim_trailing_zeros(), "1.05");
+
+    }
+
+    #[test]
+    fn keep_until_first_matched_string() {
+        assert_eq!(
+            "head BEGIN karøbα END tail END"
+                .to_string()
+                .keep("BEGIN".to_string())
+                .until_first_matched_pattern("END".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            " karøbα "
+        );
+        assert_eq!(
+            "head BEGIN karøbα END tail END"
+                .to_string()
+                .keep("BEGIN".to_string())
+                .until_first_matched_pattern("END".to_string())
+                .after_pattern()
+                .including_pattern()
+                .to_string(),
+            "BEGIN karøbα END"
+        );
+        assert_eq!(
+            "a=1; b=karøbα; c=3"
+                .to_string()
+                .keep("; c".to_string())
+                .until_first_matched_pattern("=".to_string())
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα"
+        );
+        assert_eq!(
+            "head BEGIN karøbα"
+                .to_string()
+                .keep("BEGIN".to_string())
+                .until_first_matched_pattern("END".to_string())
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn keep_until_first_matched_char() {
+        assert_eq!(
+            "(karøbα) (it was)"
+                .to_string()
+                .keep('(')
+                .end_of_string()
+                .until_first_matched_pattern(')')
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "it was"
+        );
+        assert_eq!(
+            "a,karøbα;b"
+                .to_string()
+                .keep(';')
+                .until_first_matched_pattern(',')
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            ",karøbα;"
+        );
+    }
+
+    #[test]
+    fn keep_until_no_matched() {
+        assert_eq!(
+            "key  :   karøbα"
+                .to_string()
+                .keep(':')
+                .until_no_matched_pattern(' ')
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα"
+        );
+        assert_eq!(
+            "key  :   karøbα"
+                .to_string()
+                .keep(':')
+                .until_no_matched_pattern(' ')
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "key"
+        );
+        assert_eq!(
+            "line\r\n\r\n\r\nnext"
+                .to_string()
+                .keep("\r\n".to_string())
+                .until_no_matched_pattern("\r\n".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "next"
+        );
+    }
+}
+#[cfg(test)]
+mod borrowed_input {
+    use crate::prelude::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn keep_on_borrowed_inputs() {
+        let owned = "this is karøbα it was".to_string();
+        assert_eq!(owned.as_str().keep("karøbα").to_string(), "karøbα it was");
+        assert_eq!((&owned).keep('k').excluding_pattern().to_string(), "arøbα it was");
+        assert_eq!(Cow::Borrowed(owned.as_str()).keep("is").end_of_string().to_string(), "is karøbα it was");
+        assert_eq!(Box::<str>::from(owned.as_str()).keep(' ').before_pattern().to_string(), "this ");
+        assert_eq!(std::rc::Rc::<str>::from(owned.as_str()).keep("it").to_string(), "it was");
+        assert_eq!(std::sync::Arc::<str>::from(owned.as_str()).cut(" it was").to_string(), "this is karøbα");
+    }
+
+    #[test]
+    fn keep_borrows_from_input() {
+        let text = "this is karøbα it was";
+        let kept = text.keep("karøbα").before_pattern().excluding_pattern().into_cow();
+        assert!(matches!(kept, Cow::Borrowed("this is ")));
+        assert!(std::ptr::eq(kept.as_ptr(), text.as_ptr()));
+
+        let keeper = text.cut("karøbα").after_pattern().including_pattern();
+        assert!(matches!(keeper.as_cow(), Cow::Borrowed("this is ")));
+
+        let kept = text.cut("karøbα").after_pattern().excluding_pattern().until_first_matched_pattern(" was").into_cow();
+        assert!(matches!(kept, Cow::Owned(_)));
+        assert_eq!(kept, "this is karøbα was");
+    }
+}
+
+#[cfg(test)]
+mod resolve_spans {
+    use crate::prelude::*;
+    use crate::KeeperSpan;
+
+    #[test]
+    fn resolve_reports_all_units() {
+        let resolved = "ααα karøbα 𝄞 it was"
+            .keep("karøbα")
+            .until_first_matched_pattern("it")
+            .after_pattern()
+            .excluding_pattern()
+            .utf16_encoding()
+            .resolve()
+            .unwrap();
+
+        assert_eq!(
+            resolved.pattern,
+            KeeperSpan { bytes: 7..15, chars: 4..10, utf16: Some(4..10) }
+        );
+        assert_eq!(
+            resolved.kept,
+            KeeperSpan { bytes: 15..21, chars: 10..13, utf16: Some(10..14) }
+        );
+        assert_eq!(resolved.until_pattern.unwrap().chars, 13..15);
+    }
+
+    #[test]
+    fn resolve_all_matches_single_resolves() {
+        let text = "α=1; 𝄞=karøbα; c=3;";
+        let keeper = |nth: usize| {
+            text.keep('=').after_pattern().excluding_pattern().until_first_matched_pattern(';').nth_occurrence(nth)
+        };
+        let all = keeper(0).utf16_encoding().resolve_all().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(all.len(), 3);
+        for (nth, resolved) in all.iter().enumerate() {
+            assert_eq!(Some(resolved), keeper(nth).utf16_encoding().resolve().as_ref());
+        }
+        assert_eq!(all[1].kept, KeeperSpan { bytes: 11..19, chars: 7..13, utf16: Some(8..14) });
+        assert_eq!(keeper(1).resolve_all().next().unwrap().unwrap().kept.utf16, None);
+    }
+
+    #[test]
+    fn resolve_without_utf16() {
+        let text = "this is karøbα it was";
+        let resolved = text.cut('ø').before_pattern().resolve().unwrap();
+        assert_eq!(resolved.kept.bytes, 0..13);
+        assert_eq!(resolved.kept.chars, 0..12);
+        assert_eq!(resolved.kept.utf16, None);
+        assert_eq!(&text[resolved.pattern.bytes], "ø");
+        assert_eq!(resolved.until_pattern, None);
+    }
+
+    #[test]
+    fn resolve_missing_pattern() {
+        assert_eq!("karøbα".keep("missing").resolve(), None);
+        assert_eq!("BEGIN karøbα".keep("BEGIN").until_first_matched_pattern("END").resolve(), None);
+    }
+}
+
+#[cfg(test)]
+mod keeper_fallback {
+    use crate::prelude::*;
+    use crate::{KeeperError, KeeperFallback};
+
+    #[test]
+    fn missing_pattern_is_distinguishable() {
+        assert_eq!("karøbα".keep("missing").try_to_string(), Ok("".to_string()));
+        assert_eq!(
+            "karøbα".keep("missing").set_fallback(KeeperFallback::Error).try_to_string(),
+            Err(KeeperError::PatternNotFound)
+        );
+        assert_eq!(
+            "karøbα".keep("α").after_pattern().excluding_pattern().set_fallback(KeeperFallback::Error).try_to_string(),
+            Ok("".to_string())
+        );
+        assert_eq!(
+            "BEGIN karøbα"
+                .keep("BEGIN")
+                .until_first_matched_pattern("END")
+                .set_fallback(KeeperFallback::Error)
+                .try_to_string(),
+            Err(KeeperError::UntilPatternNotFound)
+        );
+    }
+
+    #[test]
+    fn fallback_policies() {
+        assert_eq!("karøbα".keep('x').set_fallback(KeeperFallback::WholeInput).to_string(), "karøbα");
+        assert_eq!("karøbα".cut('x').to_string(), "karøbα");
+        assert_eq!("karøbα".cut('x').set_fallback(KeeperFallback::Empty).to_string(), "");
+        assert_eq!("karøbα".keep('x').set_fallback(KeeperFallback::Error).to_string(), "");
+        assert_eq!(
+            "karøbα".cut('x').set_fallback(KeeperFallback::Error).try_into_cow(),
+            Err(KeeperError::PatternNotFound)
+        );
+    }
+
+    #[test]
+    fn invalid_range_from_custom_pattern() {
+        struct Broken;
+
+        impl crate::KeeperPattern for Broken {
+            fn next_match(&self, _: &str, _: usize) -> Option<std::ops::Range<usize>> {
+                Some(4..5)
+            }
+
+            fn next_match_back(&self, haystack: &str, from: usize) -> Option<std::ops::Range<usize>> {
+                self.next_match(haystack, from)
+            }
+        }
+
+        assert_eq!(
+            "karøbα".keep(Broken).set_fallback(KeeperFallback::Error).try_to_string(),
+            Err(KeeperError::InvalidRange { start: 4, end: 5 })
+        );
+        assert_eq!("karøbα".keep(Broken).to_string(), "");
+        assert_eq!(
+            "karøbα".keep(Broken).resolve_all().collect::<Vec<_>>(),
+            [Err(KeeperError::InvalidRange { start: 4, end: 5 })]
+        );
+    }
+}
+
+#[cfg(test)]
+mod keeper_offsets {
+    use crate::prelude::*;
+    use crate::StringKeeper;
+    use proptest::prelude::*;
+
+    fn widen(c: char) -> char {
+        match c {
+            'a' => 'α',
+            'b' => '€',
+            'c' => '𝄞',
+            '0' => '０',
+            ',' => '，',
+            other => other,
+        }
+    }
+
+    fn widen_str(text: &str) -> String {
+        text.chars().map(widen).collect()
+    }
+
+    fn configure<T, P>(
+        keeper: StringKeeper<T, P>,
+        from_end: bool,
+        before: bool,
+        excluding: bool,
+    ) -> StringKeeper<T, P> {
+        let keeper = if from_end { keeper.end_of_string() } else { keeper.beginning_of_string() };
+        let keeper = if before { keeper.before_pattern() } else { keeper.after_pattern() };
+        if excluding { keeper.excluding_pattern() } else { keeper.including_pattern() }
+    }
+
+    proptest! {
+        #[test]
+        fn string_pattern_is_char_consistent(
+            text in "[abc0, ]{0,24}",
+            pattern in "[abc0, ]{1,3}",
+            until in prop::option::of(("[abc0, ]{1,2}", any::<bool>())),
+            cut in any::<bool>(),
+            from_end in any::<bool>(),
+            before in any::<bool>(),
+            excluding in any::<bool>(),
+        ) {
+            let render = |text: String, map: fn(&str) -> String| {
+                let keeper = if cut { text.cut(map(&pattern)) } else { text.keep(map(&pattern)) };
+                let keeper = match &until {
+                    Some((until, true)) => keeper.until_first_matched_pattern(map(until)),
+                    Some((until, false)) => keeper.until_no_matched_pattern(map(until)),
+                    None => keeper,
+                };
+                configure(keeper, from_end, before, excluding).to_string()
+            };
+
+            prop_assert_eq!(
+                widen_str(&render(text.clone(), str::to_string)),
+                render(widen_str(&text), widen_str)
+            );
+        }
+
+        #[test]
+        fn char_pattern_is_char_consistent(
+            text in "[abc0, ]{0,24}",
+            pattern in prop::sample::select(vec!['a', 'b', 'c', '0', ',', ' ']),
+            until in prop::option::of((prop::sample::select(vec!['a', 'b', 'c', '0', ',', ' ']), any::<bool>())),
+            cut in any::<bool>(),
+            from_end in any::<bool>(),
+            before in any::<bool>(),
+            excluding in any::<bool>(),
+        ) {
+            let render = |text: String, map: fn(char) -> char| {
+                let keeper = if cut { text.cut(map(pattern)) } else { text.keep(map(pattern)) };
+                let keeper = match until {
+                    Some((until, true)) => keeper.until_first_matched_pattern(map(until)),
+                    Some((until, false)) => keeper.until_no_matched_pattern(map(until)),
+                    None => keeper,
+                };
+                configure(keeper, from_end, before, excluding).to_string()
+            };
+
+            prop_assert_eq!(
+                widen_str(&render(text.clone(), |c| c)),
+                render(widen_str(&text), widen)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn cut_is_complement_of_keep(
+            text in "[abc0, ]{0,24}",
+            pattern in "[abc0, ]{1,3}",
+            from_end in any::<bool>(),
+            before in any::<bool>(),
+            excluding in any::<bool>(),
+        ) {
+            let kept = configure(text.clone().keep(pattern.clone()), from_end, before, excluding).to_string();
+            let cut = configure(text.clone().cut(pattern.clone()), from_end, before, excluding).to_string();
+
+            if text.contains(&pattern) {
+                prop_assert_eq!(kept.len() + cut.len(), text.len());
+                prop_assert!(text.starts_with(&kept) || text.ends_with(&kept));
+            } else {
+                prop_assert_eq!(kept, "");
+                prop_assert_eq!(cut, text);
+            }
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    proptest! {
+        #[test]
+        fn regex_pattern_is_char_consistent(
+            text in "[abc0, ]{0,24}",
+            pattern in "[abc0, ]{1,3}",
+            from_end in any::<bool>(),
+            before in any::<bool>(),
+            excluding in any::<bool>(),
+        ) {
+            let render = |text: String, pattern: String| {
+                let keeper = text.keep(regex::Regex::new(&regex::escape(&pattern)).unwrap());
+                configure(keeper, from_end, before, excluding).to_string()
+            };
+
+            prop_assert_eq!(
+                widen_str(&render(text.clone(), pattern.clone())),
+                render(widen_str(&text), widen_str(&pattern))
+            );
+        }
+    }
+
+    #[test]
+    fn multi_byte_prefix_does_not_shift_string_matches() {
+        assert_eq!(
+            "ααα karøbα it was"
+                .to_string()
+                .keep("karøbα".to_string())
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            "ααα karøbα"
+        );
+        assert_eq!(
+            "ααα karøbα it was"
+                .to_string()
+                .keep("karøbα".to_string())
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            " it was"
+        );
+    }
+}
+
+#[cfg(test)]
+mod keeper_occurrences {
+    use crate::prelude::*;
+    use crate::{KeeperError, StringKeeper};
+
+    #[test]
+    fn segments_between_delimiters() {
+        let keeper = "a,karøbα,,c"
+            .keep(',')
+            .after_pattern()
+            .excluding_pattern()
+            .until_first_matched_pattern(',');
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["karøbα", ""]);
+
+        let keeper = "a,karøbα,c".keep(',').before_pattern().excluding_pattern();
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["a", "a,karøbα"]);
+
+        let keeper = "a,karøbα,c".keep(',').end_of_string().after_pattern().including_pattern();
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), [",c", ",karøbα,c"]);
+    }
+
+    #[test]
+    fn segments_in_cut_mode() {
+        let keeper = "k=1;v=2".cut('=').after_pattern().until_first_matched_pattern(';');
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["kv=2"]);
+        assert_eq!("karøbα".cut('x').segments().count(), 0);
+    }
+
+    #[test]
+    fn segments_skip_widened_runs() {
+        let keeper = "1000200".keep('0').until_no_matched_pattern('0').before_pattern().excluding_pattern();
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["1", "10002"]);
+        assert_eq!(
+            keeper.resolve_all().map(|found| found.unwrap().pattern.chars).collect::<Vec<_>>(),
+            [1..4, 5..7]
+        );
+    }
+
+    #[test]
+    fn resolve_all_reports_unusable_matches() {
+        let keeper = "a=1; b=karøbα".keep('=').after_pattern().excluding_pattern().until_first_matched_pattern(';');
+        let resolved = keeper.resolve_all().collect::<Vec<_>>();
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].as_ref().unwrap().kept.chars, 2..3);
+        assert_eq!(resolved[1], Err(KeeperError::UntilPatternNotFound));
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["1"]);
+    }
+
+    #[test]
+    fn nth_occurrence() {
+        let text = "a=1, b=2, c=3";
+        let value = |keeper: StringKeeper<char, &str>| {
+            keeper.after_pattern().excluding_pattern().until_first_matched_pattern(',').to_string()
+        };
+        assert_eq!(value(text.keep('=').nth_occurrence(0)), "1");
+        assert_eq!(value(text.keep('=').nth_occurrence(1)), "2");
+        assert_eq!(text.keep('=').nth_occurrence(2).after_pattern().excluding_pattern().to_string(), "3");
+        assert_eq!(value(text.keep('=').nth_occurrence(3)), "");
+        assert_eq!(
+            text.keep(',').end_of_string().nth_occurrence(1).before_pattern().excluding_pattern().to_string(),
+            "a=1"
+        );
+        assert_eq!(text.cut(", ").nth_occurrence(1).before_pattern().to_string(), "c=3");
+        assert_eq!(
+            text.keep('=').nth_occurrence(1).after_pattern().excluding_pattern().segments().count(),
+            2
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn empty_regex_matches_advance() {
+        let keeper = "aøb".keep(regex::Regex::new("x*").unwrap()).after_pattern().excluding_pattern();
+        assert_eq!(keeper.segments().collect::<Vec<_>>(), ["aøb", "øb", "b", ""]);
+    }
+}
+
+#[cfg(test)]
+mod keeper_spec {
+    use crate::prelude::*;
+    use crate::{KeeperError, KeeperFallback, KeeperSpec};
+
+    struct Config {
+        value: KeeperSpec<&'static str>,
+    }
+
+    #[test]
+    fn spec_applies_to_many_inputs() {
+        let config = Config {
+            value: KeeperSpec::keep("=").after_pattern().excluding_pattern().until_first_matched_pattern(";"),
+        };
+        assert_eq!(config.value.apply("a=karøbα;"), "karøbα");
+        assert_eq!(config.value.apply("b=it was;c=1;"), "it was");
+        assert_eq!(config.value.apply("karøbα"), "");
+        assert_eq!(config.value.resolve("ø=1;").map(|found| found.kept.chars), Some(2..3));
+        assert_eq!(
+            config.value.clone().set_fallback(KeeperFallback::Error).try_apply("a=1"),
+            Err(KeeperError::UntilPatternNotFound)
+        );
+    }
+
+    #[test]
+    fn spec_round_trips_through_keeper() {
+        let keeper = "karøbα it was".cut(' ').end_of_string().before_pattern();
+        let expected = keeper.to_string();
+        let spec = keeper.into_spec();
+        assert_eq!(spec.apply("karøbα it was"), expected);
+        assert_eq!(spec.clone().bind("karøbα it was").to_string(), expected);
+        assert_eq!(spec.bind("a b".to_string()).spec().apply("c d"), "d");
+    }
+
+    #[test]
+    fn spec_is_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let spec = KeeperSpec::keep(',').end_of_string().after_pattern().excluding_pattern();
+        assert_send_sync(&spec);
+        let lines = ["a,karøbα", "b,it", "c,was"];
+        let values = std::thread::scope(|scope| {
+            lines
+                .iter()
+                .map(|line| scope.spawn(|| spec.apply(line).into_owned()))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(values, ["karøbα", "it", "was"]);
+    }
+}
+
+#[cfg(test)]
+mod trim_run {
+    use super::*;
+
+    #[test]
+    fn trim_char_runs() {
+        assert_eq!("000karøbα000".trim_run_start('0', usize::MAX), "karøbα000");
+        assert_eq!("000karøbα000".trim_run_end('0', 2), "000karøbα0");
+        assert_eq!("000karøbα000".trim_run_both('0', 1), "00karøbα00");
+        assert_eq!("0000".trim_run_both('0', usize::MAX), "");
+        assert_eq!("karøbα".trim_run_both('0', usize::MAX), "karøbα");
+        assert_eq!("100".trim_run_end('0', 0), "100");
+    }
+
+    #[test]
+    fn trim_string_runs() {
+        assert_eq!("karøbα\r\n\r\n\r\n".trim_run_end("\r\n", usize::MAX), "karøbα");
+        assert_eq!("\r\n\nkarøbα".trim_run_start("\r\n", usize::MAX), "\nkarøbα");
+        assert_eq!("     karøbα".trim_run_start("  ", usize::MAX), " karøbα");
+        assert_eq!("ababakarøbα".trim_run_start("ab", usize::MAX), "akarøbα");
+        assert_eq!("karøbα".trim_run_both("", usize::MAX), "karøbα");
+    }
+
+    #[test]
+    fn trim_predicate_runs() {
+        let line = "  \t karøbα 42 \n";
+        assert_eq!(line.trim_run_both(char::is_whitespace, usize::MAX), "karøbα 42");
+        assert_eq!("12karøbα34".trim_run_both(|c: char| c.is_ascii_digit(), 1), "2karøbα3");
+        assert_eq!("αα-karøbα".trim_run_start(['α', '-'], usize::MAX), "karøbα");
+
+        let owned = String::from("xxkarøbαxx");
+        let trimmed: &str = owned.trim_run_both('x', usize::MAX);
+        assert!(std::ptr::eq(trimmed.as_ptr(), owned[2..].as_ptr()));
+    }
+}
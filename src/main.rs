@@ -0,0 +1,346 @@
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
+use std::ops::Bound;
+
+use string_utility::prelude::*;
+use string_utility::{KeeperRule, RuleParseError};
+
+const USAGE: &str = "\
+Usage: string-utility [OPTIONS] [FILE]...
+
+Applies keep/cut rules and substrings to every line of the input files (or stdin when no file or
+`-` is given). Operations run in the order they are given, each on the result of the previous one.
+Line endings are kept, and invalid UTF-8 is replaced with U+FFFD. Files that cannot be opened or
+read are reported and skipped.
+
+Operations:
+  -e, --rule <RULE>          apply a keeper rule, e.g. \"keep after-excl '=' until-first ';'\"
+      --keep <RULE>          shorthand for --rule \"keep <RULE>\"
+      --cut <RULE>           shorthand for --rule \"cut <RULE>\"
+      --substring <RANGE>    keep the chars in RANGE, e.g. 2..5, 3.., ..=7
+      --trim-trailing-zeros  remove trailing zeros after a decimal point, e.g. 2.500 -> 2.5;
+                             integers and text that is not a number are kept as they are
+
+Options:
+  -w, --whole                process each input as one string instead of line by line
+  -h, --help                 print this help
+  -V, --version              print the version
+";
+
+enum Operation {
+    Rule(KeeperRule),
+    Substring(Bound<usize>, Bound<usize>),
+    TrimTrailingZeros,
+}
+
+impl Operation {
+    fn apply<'a>(&self, text: Cow<'a, str>) -> Cow<'a, str> {
+        match (self, text) {
+            (Operation::Rule(rule), Cow::Borrowed(text)) => rule.apply(text),
+            (Operation::Rule(rule), Cow::Owned(text)) => Cow::Owned(rule.apply(&text).into_owned()),
+            (Operation::Substring(start, end), Cow::Borrowed(text)) => Cow::Borrowed(text.substr((*start, *end))),
+            (Operation::Substring(start, end), Cow::Owned(text)) => Cow::Owned(text.substring((*start, *end))),
+            (Operation::TrimTrailingZeros, Cow::Borrowed(text)) => trim_trailing_zeros(text),
+            (Operation::TrimTrailingZeros, Cow::Owned(text)) => Cow::Owned(trim_trailing_zeros(&text).into_owned()),
+        }
+    }
+}
+
+/// Normalises the number surrounded by whitespace in `text`, leaving anything else unchanged.
+fn trim_trailing_zeros(text: &str) -> Cow<'_, str> {
+    let number = text.trim();
+    let start = text.len() - text.trim_start().len();
+    match number.normalize_number().try_as_cow() {
+        Ok(normalized) if normalized != number => {
+            Cow::Owned([&text[..start], &normalized, &text[start + number.len()..]].concat())
+        }
+        _ => Cow::Borrowed(text),
+    }
+}
+
+struct Args {
+    operations: Vec<Operation>,
+    files: Vec<String>,
+    whole: bool,
+}
+
+fn parse_rule(rule: &str) -> Result<KeeperRule, String> {
+    rule.parse().map_err(|err: RuleParseError| {
+        format!("invalid rule: {}\n  {}\n  {}^", err, rule, " ".repeat(err.position))
+    })
+}
+
+fn parse_range(range: &str) -> Result<(Bound<usize>, Bound<usize>), String> {
+    let invalid = || format!("invalid range `{}`, expected e.g. 2..5, 3.., ..=7", range);
+    let (start, end) = range.split_once("..").ok_or_else(invalid)?;
+    let index = |idx: &str| idx.trim().parse::<usize>().map_err(|_| invalid());
+
+    let start = match start.trim() {
+        "" => Bound::Unbounded,
+        start => Bound::Included(index(start)?),
+    };
+    let end = match end.strip_prefix('=') {
+        Some(end) => Bound::Included(index(end)?),
+        None if end.trim().is_empty() => Bound::Unbounded,
+        None => Bound::Excluded(index(end)?),
+    };
+    Ok((start, end))
+}
+
+fn text(value: std::ffi::OsString) -> Result<String, String> {
+    value.into_string().map_err(|_| "arguments must be valid UTF-8".to_string())
+}
+
+fn value(parser: &mut lexopt::Parser) -> Result<String, String> {
+    parser.value().map_err(|err| err.to_string()).and_then(text)
+}
+
+fn parse_args(args: impl IntoIterator<Item = std::ffi::OsString>) -> Result<Option<Args>, String> {
+    use lexopt::prelude::*;
+
+    let mut parser = lexopt::Parser::from_args(args);
+    let mut parsed = Args { operations: Vec::new(), files: Vec::new(), whole: false };
+    while let Some(arg) = parser.next().map_err(|err| err.to_string())? {
+        let operation = match arg {
+            Short('e') | Long("rule") => Operation::Rule(parse_rule(&value(&mut parser)?)?),
+            Long("keep") => Operation::Rule(parse_rule(&format!("keep {}", value(&mut parser)?))?),
+            Long("cut") => Operation::Rule(parse_rule(&format!("cut {}", value(&mut parser)?))?),
+            Long("substring") => {
+                let (start, end) = parse_range(&value(&mut parser)?)?;
+                Operation::Substring(start, end)
+            }
+            Long("trim-trailing-zeros") => Operation::TrimTrailingZeros,
+            Short('w') | Long("whole") => {
+                parsed.whole = true;
+                continue;
+            }
+            Short('h') | Long("help") => {
+                print!("{}", USAGE);
+                return Ok(None);
+            }
+            Short('V') | Long("version") => {
+                println!("string-utility {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            Value(file) => {
+                parsed.files.push(text(file)?);
+                continue;
+            }
+            arg => return Err(arg.unexpected().to_string()),
+        };
+        parsed.operations.push(operation);
+    }
+    Ok(Some(parsed))
+}
+
+fn transform<'a>(operations: &[Operation], text: &'a str) -> Cow<'a, str> {
+    operations
+        .iter()
+        .fold(Cow::Borrowed(text), |text, operation| operation.apply(text))
+}
+
+/// An I/O error while processing one input, telling the input apart from the output.
+#[derive(Debug)]
+enum ProcessError {
+    Read(std::io::Error),
+    Write(std::io::Error),
+}
+
+fn process(args: &Args, mut input: impl BufRead, output: &mut impl Write) -> Result<(), ProcessError> {
+    let mut buffer = Vec::new();
+    if args.whole {
+        input.read_to_end(&mut buffer).map_err(ProcessError::Read)?;
+        return output
+            .write_all(transform(&args.operations, &String::from_utf8_lossy(&buffer)).as_bytes())
+            .map_err(ProcessError::Write);
+    }
+
+    while input.read_until(b'\n', &mut buffer).map_err(ProcessError::Read)? > usize::MIN {
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let (line, ending) = buffer.split_at(line.len());
+        output
+            .write_all(transform(&args.operations, &String::from_utf8_lossy(line)).as_bytes())
+            .and_then(|()| output.write_all(ending))
+            .map_err(ProcessError::Write)?;
+        buffer.clear();
+    }
+    Ok(())
+}
+
+/// Processes every input file, reporting those that cannot be opened or read to `errors`. Returns
+/// whether all of them could be read; only output errors end the run.
+fn run(args: &Args, output: &mut impl Write, errors: &mut impl Write) -> Result<bool, String> {
+    let files = if args.files.is_empty() { vec!["-".to_string()] } else { args.files.clone() };
+
+    let mut all_read = true;
+    for file in &files {
+        let result = if file == "-" {
+            process(args, std::io::stdin().lock(), output)
+        } else {
+            std::fs::File::open(file)
+                .map_err(ProcessError::Read)
+                .and_then(|opened| process(args, std::io::BufReader::new(opened), output))
+        };
+        match result {
+            Ok(()) => {}
+            Err(ProcessError::Read(err)) => {
+                let _ = writeln!(errors, "string-utility: {}: {}", file, err);
+                all_read = false;
+            }
+            Err(ProcessError::Write(err)) if err.kind() == std::io::ErrorKind::BrokenPipe => return Ok(all_read),
+            Err(ProcessError::Write(err)) => return Err(err.to_string()),
+        }
+    }
+    match output.flush() {
+        Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => Err(err.to_string()),
+        _ => Ok(all_read),
+    }
+}
+
+fn main() {
+    let code = match parse_args(std::env::args_os().skip(1)) {
+        Ok(Some(args)) => match run(&args, &mut std::io::BufWriter::new(std::io::stdout().lock()), &mut std::io::stderr())
+        {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(err) => {
+                eprintln!("string-utility: {}", err);
+                1
+            }
+        },
+        Ok(None) => 0,
+        Err(err) => {
+            eprintln!("string-utility: {}\nTry 'string-utility --help' for more information.", err);
+            2
+        }
+    };
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(args: &[&str]) -> Args {
+        parse_args(args.iter().map(std::ffi::OsString::from)).unwrap().unwrap()
+    }
+
+    fn run_on(args: &Args, input: &str) -> String {
+        String::from_utf8(run_on_bytes(args, input.as_bytes())).unwrap()
+    }
+
+    fn run_on_bytes(args: &Args, input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        process(args, input, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn line_by_line() {
+        let args = parsed(&["-e", "keep after-excl '=' until-first ';'", "--cut", "before c' ' until-none c' '"]);
+        assert_eq!(run_on(&args, "a = karøbα;\nb=  it was;\nnone\n"), "karøbα\nit was\n\n");
+    }
+
+    #[test]
+    fn whole_input_and_substring() {
+        let args = parsed(&["--whole", "--substring", "2..=5"]);
+        assert_eq!(run_on(&args, "kaøb\nα it"), "øb\nα");
+        let args = parsed(&["--substring", "3..", "--trim-trailing-zeros"]);
+        assert_eq!(run_on(&args, "ab:1.500\nab:2\n"), "1.5\n2\n");
+    }
+
+    #[test]
+    fn trim_trailing_zeros_keeps_integers() {
+        let args = parsed(&["--trim-trailing-zeros"]);
+        assert_eq!(run_on(&args, "100\n2.500\n-0.0\n3.\nkarøbα 10\n"), "100\n2.5\n-0\n3\nkarøbα 10\n");
+        assert_eq!(run_on(&args, " 1.500 \r\n\t20\n"), " 1.5 \r\n\t20\n");
+        let trimmed = Operation::TrimTrailingZeros.apply(Cow::Owned("1.2500".to_string()));
+        assert_eq!(trimmed, "1.25");
+    }
+
+    #[test]
+    fn line_endings_and_invalid_utf8_are_kept() {
+        let args = parsed(&["--keep", "after-excl '='"]);
+        assert_eq!(run_on(&args, "a=karøbα\r\nb=it was\nc=last"), "karøbα\r\nit was\nlast");
+        assert_eq!(run_on_bytes(&args, b"a=\xff\xfe\nb=karoba\n"), "\u{FFFD}\u{FFFD}\nkaroba\n".as_bytes());
+        assert_eq!(run_on(&args, ""), "");
+    }
+
+    #[test]
+    fn missing_files_are_skipped() {
+        let existing = std::env::temp_dir().join(format!("string-utility-{}.txt", std::process::id()));
+        std::fs::write(&existing, "a=karøbα\n").unwrap();
+        let missing = existing.with_extension("missing");
+        let mut args = parsed(&["--keep", "after-excl '='"]);
+        args.files = vec![missing.display().to_string(), existing.display().to_string()];
+
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let all_read = run(&args, &mut output, &mut errors);
+        std::fs::remove_file(&existing).unwrap();
+        assert_eq!(all_read, Ok(false));
+        assert_eq!(String::from_utf8(output).unwrap(), "karøbα\n");
+        let errors = String::from_utf8(errors).unwrap();
+        assert!(errors.starts_with(&format!("string-utility: {}: ", missing.display())), "{}", errors);
+    }
+
+    #[test]
+    fn unreadable_files_are_skipped() {
+        // a directory opens fine on unix but fails on the first read
+        let directory = std::env::temp_dir();
+        let existing = directory.join(format!("string-utility-read-{}.txt", std::process::id()));
+        std::fs::write(&existing, "a=karøbα\n").unwrap();
+        let mut args = parsed(&["--keep", "after-excl '='"]);
+        args.files = vec![directory.display().to_string(), existing.display().to_string()];
+
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let all_read = run(&args, &mut output, &mut errors);
+        std::fs::remove_file(&existing).unwrap();
+        assert_eq!(all_read, Ok(false));
+        assert_eq!(String::from_utf8(output).unwrap(), "karøbα\n");
+        let errors = String::from_utf8(errors).unwrap();
+        assert!(errors.starts_with(&format!("string-utility: {}: ", directory.display())), "{}", errors);
+    }
+
+    #[test]
+    fn output_errors_do_not_blame_the_input() {
+        struct Full;
+
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("no space left"))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let existing = std::env::temp_dir().join(format!("string-utility-write-{}.txt", std::process::id()));
+        std::fs::write(&existing, "karøbα\n").unwrap();
+        let mut args = parsed(&[]);
+        args.files = vec![existing.display().to_string()];
+
+        let mut errors = Vec::new();
+        let result = run(&args, &mut Full, &mut errors);
+        std::fs::remove_file(&existing).unwrap();
+        assert_eq!(result, Err("no space left".to_string()));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_range("2..5"), Ok((Bound::Included(2), Bound::Excluded(5))));
+        assert_eq!(parse_range("..=7"), Ok((Bound::Unbounded, Bound::Included(7))));
+        assert_eq!(parse_range(".."), Ok((Bound::Unbounded, Bound::Unbounded)));
+        assert!(parse_range("5").is_err());
+        assert!(parse_range("a..b").is_err());
+    }
+
+    #[test]
+    fn rule_errors_point_at_position() {
+        let err = parse_args(["--keep", "sideways 'x'"].map(std::ffi::OsString::from)).err().unwrap();
+        assert_eq!(err, "invalid rule: unknown side `sideways` at position 5\n  keep sideways 'x'\n       ^");
+        assert!(parse_args(["--bogus"].map(std::ffi::OsString::from)).is_err());
+    }
+}
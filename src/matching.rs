@@ -0,0 +1,319 @@
+use std::ops::Range;
+
+use crate::KeeperPattern;
+
+/// How string and char patterns are compared with the input. Matches are always reported as
+/// ranges of the original text; other pattern kinds always match exactly.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum KeeperMatching {
+    Exact,
+    AsciiCaseInsensitive,
+    /// Full Unicode default case folding, so `"STRASSE"` matches `"straße"`.
+    #[cfg(feature = "caseless")]
+    CaseFold,
+    /// Compares the canonical compositions, so a precomposed `"é"` matches `"e\u{301}"`.
+    #[cfg(feature = "unicode-normalization")]
+    Nfc,
+    /// Like `Nfc`, but also folds compatibility characters such as `"ﬁ"` into `"fi"`.
+    #[cfg(feature = "unicode-normalization")]
+    Nfkc,
+}
+
+impl KeeperMatching {
+    fn fold(&self, text: &str) -> String {
+        let mut folded = String::with_capacity(text.len());
+        self.fold_into(text, &mut folded);
+        folded
+    }
+
+    fn fold_into(&self, text: &str, folded: &mut String) {
+        match self {
+            KeeperMatching::Exact => folded.push_str(text),
+            KeeperMatching::AsciiCaseInsensitive => folded.extend(text.chars().map(|c| c.to_ascii_lowercase())),
+            #[cfg(feature = "caseless")]
+            KeeperMatching::CaseFold => folded.extend(caseless::Caseless::default_case_fold(text.chars())),
+            #[cfg(feature = "unicode-normalization")]
+            KeeperMatching::Nfc => folded.extend(unicode_normalization::UnicodeNormalization::nfc(text)),
+            #[cfg(feature = "unicode-normalization")]
+            KeeperMatching::Nfkc => folded.extend(unicode_normalization::UnicodeNormalization::nfkc(text)),
+        }
+    }
+
+    /// Whether folding keeps every char at its byte offset.
+    fn keeps_offsets(&self) -> bool {
+        matches!(self, KeeperMatching::Exact | KeeperMatching::AsciiCaseInsensitive)
+    }
+
+    /// Whether a folded segment may start at `c`. Normalization may combine a char with the one
+    /// before it, so a segment only starts where the decomposition of `c` begins with a starter
+    /// that never composes backwards (NFC quick check "Yes"). This keeps combining marks and
+    /// the vowel and trailing jamo of a decomposed Hangul syllable with their base.
+    #[cfg_attr(not(feature = "unicode-normalization"), allow(unused_variables))]
+    fn starts_segment(&self, c: char) -> bool {
+        match self {
+            #[cfg(feature = "unicode-normalization")]
+            KeeperMatching::Nfc | KeeperMatching::Nfkc => {
+                use unicode_normalization::{char as normalization, IsNormalized};
+
+                let mut first = None;
+                let mut first_char = |decomposed: char| {
+                    first.get_or_insert(decomposed);
+                };
+                if *self == KeeperMatching::Nfc {
+                    normalization::decompose_canonical(c, &mut first_char);
+                } else {
+                    normalization::decompose_compatible(c, &mut first_char);
+                }
+                first.is_some_and(|first| {
+                    normalization::canonical_combining_class(first) == 0
+                        && unicode_normalization::is_nfc_quick(std::iter::once(first)) == IsNormalized::Yes
+                })
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A text folded segment by segment, remembering where each segment starts in the folded and the
+/// original text. Matches are only accepted on segment boundaries.
+pub(crate) struct FoldedText {
+    folded: String,
+    /// `None` when every char is its own segment at an unchanged offset.
+    starts: Option<Vec<(usize, usize)>>,
+}
+
+impl FoldedText {
+    fn new(text: &str, matching: &KeeperMatching) -> FoldedText {
+        if matching.keeps_offsets() {
+            return FoldedText { folded: matching.fold(text), starts: None };
+        }
+
+        let mut boundaries = text
+            .char_indices()
+            .filter(|(pos, c)| *pos == usize::MIN || matching.starts_segment(*c))
+            .map(|(pos, _)| pos)
+            .chain(std::iter::once(text.len()))
+            .peekable();
+
+        let mut folded = String::with_capacity(text.len());
+        let mut starts = Vec::new();
+        while let Some(start) = boundaries.next() {
+            starts.push((folded.len(), start));
+            if let Some(end) = boundaries.peek() {
+                matching.fold_into(&text[start..*end], &mut folded);
+            }
+        }
+        FoldedText { folded, starts: Some(starts) }
+    }
+
+    /// The folded offset of the first segment starting at or after `original`.
+    fn folded_after(&self, original: usize) -> usize {
+        let Some(starts) = &self.starts else {
+            return original;
+        };
+        let idx = starts.partition_point(|(_, start)| *start < original);
+        starts.get(idx).map_or(self.folded.len(), |(folded, _)| *folded)
+    }
+
+    /// The folded offset of the last segment starting at or before `original`.
+    fn folded_before(&self, original: usize) -> usize {
+        let Some(starts) = &self.starts else {
+            return original;
+        };
+        let idx = starts.partition_point(|(_, start)| *start <= original);
+        starts[..idx].last().map_or(usize::MIN, |(folded, _)| *folded)
+    }
+
+    fn original(&self, folded: usize) -> Option<usize> {
+        let Some(starts) = &self.starts else {
+            return Some(folded).filter(|folded| self.folded.is_char_boundary(*folded));
+        };
+        let idx = starts.partition_point(|(start, _)| *start < folded);
+        starts.get(idx).filter(|(start, _)| *start == folded).map(|(_, original)| *original)
+    }
+
+    fn original_range(&self, folded: Range<usize>) -> Option<Range<usize>> {
+        Some(self.original(folded.start)?..self.original(folded.end)?)
+    }
+}
+
+/// A keeper pattern as seen through a [`KeeperMatching`] mode.
+pub(crate) enum Matcher<'k, T> {
+    Exact(&'k T),
+    Folded { needle: String, text: FoldedText },
+}
+
+impl<'k, T: KeeperPattern> Matcher<'k, T> {
+    pub(crate) fn new(pattern: &'k T, matching: &KeeperMatching, haystack: &str) -> Matcher<'k, T> {
+        match (matching, pattern.literal()) {
+            (KeeperMatching::Exact, _) | (_, None) => Matcher::Exact(pattern),
+            (matching, Some(literal)) => Matcher::Folded {
+                needle: matching.fold(&literal),
+                text: FoldedText::new(haystack, matching),
+            },
+        }
+    }
+}
+
+impl<T: KeeperPattern> KeeperPattern for Matcher<'_, T> {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        let (needle, text) = match self {
+            Matcher::Exact(pattern) => return pattern.next_match(haystack, from),
+            Matcher::Folded { needle, text } => (needle, text),
+        };
+
+        let mut from = text.folded_after(from);
+        loop {
+            let start = from + text.folded[from..].find(needle.as_str())?;
+            if let Some(found) = text.original_range(start..start + needle.len()) {
+                return Some(found);
+            }
+            from = start + text.folded[start..].chars().next()?.len_utf8();
+        }
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        let (needle, text) = match self {
+            Matcher::Exact(pattern) => return pattern.next_match_back(haystack, to),
+            Matcher::Folded { needle, text } => (needle, text),
+        };
+
+        let mut to = text.folded_before(to);
+        loop {
+            let start = text.folded[..to].rfind(needle.as_str())?;
+            let end = start + needle.len();
+            if let Some(found) = text.original_range(start..end) {
+                return Some(found);
+            }
+            to = end - text.folded[..end].chars().next_back()?.len_utf8();
+        }
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        match self {
+            Matcher::Exact(pattern) => pattern.match_at(haystack, at),
+            Matcher::Folded { .. } => self
+                .next_match(haystack, at)
+                .filter(|found| found.start == at)
+                .map(|found| found.end),
+        }
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        match self {
+            Matcher::Exact(pattern) => pattern.match_back_at(haystack, at),
+            Matcher::Folded { .. } => self
+                .next_match_back(haystack, at)
+                .filter(|found| found.end == at)
+                .map(|found| found.start),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::KeeperMatching;
+
+    #[test]
+    fn ascii_case_insensitive() {
+        let log = "[12] ERROR: karøbα Error: it was";
+        let keeper = |log: &'static str| {
+            log
+                .keep("error:")
+                .set_matching(KeeperMatching::AsciiCaseInsensitive)
+                .after_pattern()
+                .excluding_pattern()
+        };
+        assert_eq!(keeper(log).to_string(), " karøbα Error: it was");
+        assert_eq!(keeper(log).end_of_string().to_string(), " it was");
+        assert_eq!(keeper(log).resolve().unwrap().pattern.bytes, 5..11);
+        assert_eq!(log.keep("error:").to_string(), "");
+        assert_eq!(
+            log.keep('e').set_matching(KeeperMatching::AsciiCaseInsensitive).nth_occurrence(1).to_string(),
+            "Error: it was"
+        );
+        assert_eq!(
+            "KARØBΑ".keep("karøbα").set_matching(KeeperMatching::AsciiCaseInsensitive).to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn until_pattern_is_folded() {
+        assert_eq!(
+            "BEGIN karøbα End it"
+                .keep("begin")
+                .until_first_matched_pattern("end")
+                .set_matching(KeeperMatching::AsciiCaseInsensitive)
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            " karøbα "
+        );
+    }
+
+    #[test]
+    fn non_literal_patterns_match_exactly() {
+        let is_upper = |c: char| c.is_uppercase();
+        assert_eq!(
+            "karøbα It".keep(is_upper).set_matching(KeeperMatching::AsciiCaseInsensitive).to_string(),
+            "It"
+        );
+    }
+
+    #[cfg(feature = "caseless")]
+    #[test]
+    fn unicode_case_folding() {
+        let text = "Die STRASSE heißt Karøbα";
+        let keeper = text.keep("straße").set_matching(KeeperMatching::CaseFold).before_pattern().excluding_pattern();
+        assert_eq!(keeper.to_string(), "Die ");
+        assert_eq!(
+            text.keep("KARØBΑ").set_matching(KeeperMatching::CaseFold).resolve().unwrap().kept.chars,
+            18..24
+        );
+        // "ß" folds to "ss", so a single "s" must not match half of it
+        assert_eq!("heißt".keep('s').set_matching(KeeperMatching::CaseFold).to_string(), "");
+        assert_eq!(
+            "Maße MASSE".keep("ss").set_matching(KeeperMatching::CaseFold).end_of_string().resolve().unwrap().kept.bytes,
+            8..11
+        );
+        assert_eq!("Maße".keep("SS").set_matching(KeeperMatching::CaseFold).to_string(), "ße");
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalized_matching() {
+        let decomposed = "cafe\u{301} ou the\u{301}";
+        assert_eq!(decomposed.keep("café").to_string(), "");
+        assert_eq!(
+            decomposed.keep("café").set_matching(KeeperMatching::Nfc).before_pattern().to_string(),
+            "cafe\u{301}"
+        );
+        assert_eq!(
+            decomposed.keep("thé").set_matching(KeeperMatching::Nfc).resolve().unwrap().pattern.bytes,
+            10..15
+        );
+        // a base char followed by a combining mark is not a match for the base char alone
+        assert_eq!(decomposed.keep('e').set_matching(KeeperMatching::Nfc).nth_occurrence(1).to_string(), "");
+        assert_eq!("ﬁle".keep("fi").set_matching(KeeperMatching::Nfc).to_string(), "");
+        assert_eq!("ﬁle".keep("fi").set_matching(KeeperMatching::Nfkc).to_string(), "ﬁle");
+        assert_eq!("a ﬁle".keep("fi").set_matching(KeeperMatching::Nfkc).to_string(), "ﬁle");
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn decomposed_hangul() {
+        // "각" as leading, vowel and trailing jamo, and as the syllable "가" with a trailing jamo
+        let decomposed = "karøbα \u{1100}\u{1161}\u{11A8} it was";
+        let keeper = decomposed.keep("각").set_matching(KeeperMatching::Nfc);
+        assert_eq!(keeper.resolve().unwrap().pattern.bytes, 9..18);
+        assert_eq!(keeper.before_pattern().excluding_pattern().to_string(), "karøbα ");
+        assert_eq!("\u{AC00}\u{11A8}".keep("각").set_matching(KeeperMatching::Nfc).to_string(), "\u{AC00}\u{11A8}");
+        // the syllable without its trailing jamo, and the leading jamo alone, are not matches
+        assert_eq!(decomposed.keep("가").set_matching(KeeperMatching::Nfc).to_string(), "");
+        assert_eq!(decomposed.keep('\u{1100}').set_matching(KeeperMatching::Nfkc).to_string(), "");
+    }
+}
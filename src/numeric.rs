@@ -0,0 +1,669 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::NumberError;
+
+pub trait NumericExt {
+    /// Normalises a decimal number such as `"+007.2300e10"`: trailing fraction zeros and a
+    /// dangling decimal separator are removed, integer zeros are kept.
+    fn normalize_number(&self) -> NumberNormalizer<'_>;
+
+    /// Pads or rounds a decimal number to exactly `places` fraction digits, working on the digits
+    /// themselves so `"0.1"` stays exact. Signs are kept, except the `'-'` of a number that only
+    /// rounds to zero: `"-0.001"` becomes `"0.00"`, `"-0.00"` stays as it is.
+    fn fixed_decimals(&self, places: usize) -> FixedDecimals<'_>;
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+pub struct NumberNormalizer<'a> {
+    text: &'a str,
+    decimal: char,
+    strip_leading_zeros: bool,
+    strip_sign: bool,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+pub struct FixedDecimals<'a> {
+    text: &'a str,
+    places: usize,
+    decimal: char,
+    rounding: DecimalRounding,
+}
+
+/// How [`fixed_decimals`](NumericExt::fixed_decimals) drops surplus fraction digits.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DecimalRounding {
+    /// `"2.5"` becomes `"3"` and `"-2.5"` becomes `"-3"`.
+    HalfAwayFromZero,
+    /// Ties go to the even digit, so `"2.5"` becomes `"2"` and `"3.5"` becomes `"4"`.
+    HalfEven,
+    /// Truncates.
+    TowardZero,
+}
+
+/// Where the separators go in the integer digits, counted from the decimal separator.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GroupingStyle {
+    /// Groups of three: `1,234,567`.
+    Thousands,
+    /// A group of three, then groups of two: `12,34,567`.
+    Indian,
+}
+
+/// Digit grouping for decimal numbers, e.g. `1,234,567.89` or with
+/// `separator('.').decimal_separator(',')` the German `1.234.567,89`. Fraction digits and
+/// exponents are never grouped.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd, PartialEq)]
+pub struct DigitGrouping {
+    separator: char,
+    decimal: char,
+    style: GroupingStyle,
+}
+
+/// Where the pieces of a decimal number lie in the text, as byte ranges.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct NumberParts {
+    pub(crate) sign: Option<Range<usize>>,
+    pub(crate) integer: Range<usize>,
+    pub(crate) separator: Option<Range<usize>>,
+    pub(crate) fraction: Range<usize>,
+    pub(crate) exponent: Option<ExponentParts>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ExponentParts {
+    pub(crate) marker: Range<usize>,
+    pub(crate) sign: Option<Range<usize>>,
+    pub(crate) digits: Range<usize>,
+}
+
+impl NumericExt for str {
+    fn normalize_number(&self) -> NumberNormalizer<'_> {
+        NumberNormalizer {
+            text: self,
+            decimal: '.',
+            strip_leading_zeros: false,
+            strip_sign: false,
+        }
+    }
+
+    fn fixed_decimals(&self, places: usize) -> FixedDecimals<'_> {
+        FixedDecimals {
+            text: self,
+            places,
+            decimal: '.',
+            rounding: DecimalRounding::HalfAwayFromZero,
+        }
+    }
+}
+
+fn char_position(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+fn digits_end(text: &str, start: usize) -> usize {
+    text[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(text.len(), |pos| start + pos)
+}
+
+fn sign_at(text: &str, at: usize) -> Option<Range<usize>> {
+    text[at..]
+        .starts_with(['+', '-'])
+        .then_some(at..at + 1)
+}
+
+/// Splits `[sign] digits [decimal digits] [(e|E) [sign] digits]`; at least one mantissa digit is
+/// required.
+pub(crate) fn parse_number(text: &str, decimal: char) -> Result<NumberParts, NumberError> {
+    let sign = sign_at(text, usize::MIN);
+    let integer_start = sign.as_ref().map_or(usize::MIN, |sign| sign.end);
+    let integer = integer_start..digits_end(text, integer_start);
+    let separator = text[integer.end..]
+        .starts_with(decimal)
+        .then(|| integer.end..integer.end + decimal.len_utf8());
+    let fraction_start = separator.as_ref().map_or(integer.end, |separator| separator.end);
+    let fraction = fraction_start..digits_end(text, fraction_start);
+    if integer.is_empty() && fraction.is_empty() {
+        let position = char_position(text, fraction.end);
+        return Err(if fraction.end < text.len() {
+            NumberError::InvalidChar { position }
+        } else {
+            NumberError::MissingDigits { position }
+        });
+    }
+
+    let exponent = if text[fraction.end..].starts_with(['e', 'E']) {
+        let marker = fraction.end..fraction.end + 1;
+        let sign = sign_at(text, marker.end);
+        let digits_start = sign.as_ref().map_or(marker.end, |sign| sign.end);
+        let digits = digits_start..digits_end(text, digits_start);
+        if digits.is_empty() {
+            return Err(NumberError::MissingDigits { position: char_position(text, digits.end) });
+        }
+        Some(ExponentParts { marker, sign, digits })
+    } else {
+        None
+    };
+
+    let end = exponent.as_ref().map_or(fraction.end, |exponent| exponent.digits.end);
+    if end < text.len() {
+        return Err(NumberError::InvalidChar { position: char_position(text, end) });
+    }
+    Ok(NumberParts { sign, integer, separator, fraction, exponent })
+}
+
+/// Skips leading `'0'`s, keeping at least one digit.
+fn trim_leading_zeros(text: &str, mut digits: Range<usize>) -> Range<usize> {
+    while digits.len() > 1 && text[digits.clone()].starts_with('0') {
+        digits.start += 1;
+    }
+    digits
+}
+
+/// Concatenates byte ranges of `text`, borrowing when they are adjacent.
+pub(crate) fn join_ranges<'a>(text: &'a str, ranges: impl IntoIterator<Item = Range<usize>>) -> Cow<'a, str> {
+    let ranges = ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .collect::<Vec<Range<usize>>>();
+    let contiguous = ranges.windows(2).all(|pair| pair[0].end == pair[1].start);
+    match (ranges.first(), ranges.last()) {
+        (Some(first), Some(last)) if contiguous => Cow::Borrowed(&text[first.start..last.end]),
+        (Some(_), Some(_)) => Cow::Owned(ranges.into_iter().map(|range| &text[range]).collect()),
+        _ => Cow::Borrowed(""),
+    }
+}
+
+impl<'a> NumberNormalizer<'a> {
+    /// The char between the integer and the fraction digits; `'.'` by default.
+    pub fn decimal_separator(mut self, decimal: char) -> NumberNormalizer<'a> {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Shorthand for `decimal_separator(',')`.
+    pub fn comma_decimal(self) -> NumberNormalizer<'a> {
+        self.decimal_separator(',')
+    }
+
+    /// Also removes leading zeros of the integer part and of the exponent, so `"007.5e05"`
+    /// becomes `"7.5e5"`. A single zero before the separator is kept.
+    pub fn strip_leading_zeros(mut self) -> NumberNormalizer<'a> {
+        self.strip_leading_zeros = true;
+        self
+    }
+
+    /// Also removes signs that carry no information: every `'+'` and the `'-'` of a zero.
+    pub fn strip_sign(mut self) -> NumberNormalizer<'a> {
+        self.strip_sign = true;
+        self
+    }
+
+    /// The normalised number, borrowed from the input when it is a single slice of it. Input that
+    /// is not a number is returned unchanged.
+    pub fn as_cow(&self) -> Cow<'a, str> {
+        self.try_as_cow().unwrap_or(Cow::Borrowed(self.text))
+    }
+
+    pub fn try_as_cow(&self) -> Result<Cow<'a, str>, NumberError> {
+        let text = self.text;
+        let parts = parse_number(text, self.decimal)?;
+        let is_zero = text[parts.integer.start..parts.fraction.end]
+            .chars()
+            .all(|c| c == '0' || !c.is_ascii_digit());
+
+        let sign = parts
+            .sign
+            .filter(|sign| !self.strip_sign || (&text[sign.clone()] == "-" && !is_zero));
+        let mut integer = if self.strip_leading_zeros {
+            trim_leading_zeros(text, parts.integer)
+        } else {
+            parts.integer
+        };
+        let mut fraction = parts.fraction.clone();
+        while text[fraction.clone()].ends_with('0') {
+            fraction.end -= 1;
+        }
+        if integer.is_empty() && fraction.is_empty() {
+            // ".000" still needs a digit
+            integer = parts.fraction.start..parts.fraction.start + 1;
+        }
+        let separator = parts.separator.filter(|_| !fraction.is_empty());
+
+        let exponent = parts.exponent.into_iter().flat_map(|exponent| {
+            let sign = exponent
+                .sign
+                .filter(|sign| !self.strip_sign || &text[sign.clone()] == "-");
+            let digits = if self.strip_leading_zeros {
+                trim_leading_zeros(text, exponent.digits)
+            } else {
+                exponent.digits
+            };
+            [Some(exponent.marker), sign, Some(digits)].into_iter().flatten()
+        });
+
+        Ok(join_ranges(
+            text,
+            [sign, Some(integer), separator, Some(fraction)]
+                .into_iter()
+                .flatten()
+                .chain(exponent),
+        ))
+    }
+
+    pub fn try_to_string(&self) -> Result<String, NumberError> {
+        self.try_as_cow().map(Cow::into_owned)
+    }
+}
+
+impl std::fmt::Display for NumberNormalizer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_cow())
+    }
+}
+
+impl<'a> FixedDecimals<'a> {
+    /// The char between the integer and the fraction digits; `'.'` by default.
+    pub fn decimal_separator(mut self, decimal: char) -> FixedDecimals<'a> {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Shorthand for `decimal_separator(',')`.
+    pub fn comma_decimal(self) -> FixedDecimals<'a> {
+        self.decimal_separator(',')
+    }
+
+    /// [`DecimalRounding::HalfAwayFromZero`] by default.
+    pub fn set_rounding(mut self, rounding: DecimalRounding) -> FixedDecimals<'a> {
+        self.rounding = rounding;
+        self
+    }
+
+    /// The formatted number, borrowed from the input when it already has `places` fraction
+    /// digits. Input that is not a number is returned unchanged.
+    pub fn as_cow(&self) -> Cow<'a, str> {
+        self.try_as_cow().unwrap_or(Cow::Borrowed(self.text))
+    }
+
+    /// Like [`as_cow`](FixedDecimals::as_cow), but rejects input that is not a number, including
+    /// numbers in exponent notation.
+    pub fn try_as_cow(&self) -> Result<Cow<'a, str>, NumberError> {
+        let text = self.text;
+        let parts = parse_number(text, self.decimal)?;
+        if let Some(exponent) = parts.exponent {
+            return Err(NumberError::InvalidChar { position: char_position(text, exponent.marker.start) });
+        }
+        let fraction = &text[parts.fraction.clone()];
+        if !parts.integer.is_empty()
+            && fraction.len() == self.places
+            && parts.separator.is_some() == (self.places > usize::MIN) {
+            return Ok(Cow::Borrowed(text));
+        }
+
+        let (kept, dropped) = fraction.split_at(self.places.min(fraction.len()));
+        let mut digits = if parts.integer.is_empty() {
+            "0".to_string()
+        } else {
+            text[parts.integer.clone()].to_string()
+        };
+        digits.push_str(kept);
+        digits.extend(std::iter::repeat_n('0', self.places - kept.len()));
+        if self.rounds_up(&digits, dropped) {
+            increment(&mut digits);
+        }
+
+        let is_zero = |digits: &str| digits.bytes().all(|digit| digit == b'0');
+        let rounded_to_zero = is_zero(&digits) && !(is_zero(&text[parts.integer.clone()]) && is_zero(fraction));
+        let sign = parts
+            .sign
+            .map(|sign| &text[sign])
+            .filter(|sign| *sign == "+" || !rounded_to_zero)
+            .unwrap_or_default();
+        let (integer, fraction) = digits.split_at(digits.len() - self.places);
+        let mut fixed = String::with_capacity(sign.len() + digits.len() + self.decimal.len_utf8());
+        fixed.push_str(sign);
+        fixed.push_str(integer);
+        if !fraction.is_empty() {
+            fixed.push(self.decimal);
+            fixed.push_str(fraction);
+        }
+        Ok(Cow::Owned(fixed))
+    }
+
+    fn rounds_up(&self, kept: &str, dropped: &str) -> bool {
+        let mut dropped = dropped.bytes();
+        let Some(first) = dropped.next() else {
+            return false;
+        };
+        match self.rounding {
+            DecimalRounding::HalfAwayFromZero => first >= b'5',
+            DecimalRounding::HalfEven => {
+                let odd = kept.bytes().next_back().is_some_and(|digit| (digit - b'0') % 2 == 1);
+                first > b'5' || (first == b'5' && (odd || dropped.any(|digit| digit != b'0')))
+            }
+            DecimalRounding::TowardZero => false,
+        }
+    }
+
+    pub fn try_to_string(&self) -> Result<String, NumberError> {
+        self.try_as_cow().map(Cow::into_owned)
+    }
+}
+
+impl std::fmt::Display for FixedDecimals<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_cow())
+    }
+}
+
+/// Adds one to a string of ASCII digits, growing it when every digit was a `'9'`.
+fn increment(digits: &mut String) {
+    let mut bytes = std::mem::take(digits).into_bytes();
+    match bytes.iter().rposition(|digit| *digit != b'9') {
+        Some(pos) => {
+            bytes[pos] += 1;
+            bytes[pos + 1..].fill(b'0');
+        }
+        None => {
+            bytes.fill(b'0');
+            bytes.insert(usize::MIN, b'1');
+        }
+    }
+    *digits = String::from_utf8(bytes).unwrap_or_default();
+}
+
+impl GroupingStyle {
+    /// The size of the group next to the decimal separator, and of every group before it.
+    fn sizes(&self) -> (usize, usize) {
+        match self {
+            GroupingStyle::Thousands => (3, 3),
+            GroupingStyle::Indian => (3, 2),
+        }
+    }
+}
+
+impl NumberError {
+    fn shifted(self, by: usize) -> NumberError {
+        match self {
+            NumberError::InvalidChar { position } => NumberError::InvalidChar { position: position + by },
+            NumberError::MissingDigits { position } => NumberError::MissingDigits { position: position + by },
+        }
+    }
+}
+
+impl Default for DigitGrouping {
+    fn default() -> DigitGrouping {
+        DigitGrouping::new()
+    }
+}
+
+impl DigitGrouping {
+    /// Thousands grouped with `','`, and `'.'` as the decimal separator.
+    pub fn new() -> DigitGrouping {
+        DigitGrouping {
+            separator: ',',
+            decimal: '.',
+            style: GroupingStyle::Thousands,
+        }
+    }
+
+    pub fn separator(mut self, separator: char) -> DigitGrouping {
+        self.separator = separator;
+        self
+    }
+
+    pub fn decimal_separator(mut self, decimal: char) -> DigitGrouping {
+        self.decimal = decimal;
+        self
+    }
+
+    pub fn set_style(mut self, style: GroupingStyle) -> DigitGrouping {
+        self.style = style;
+        self
+    }
+
+    /// Shorthand for `set_style(GroupingStyle::Indian)`.
+    pub fn indian(self) -> DigitGrouping {
+        self.set_style(GroupingStyle::Indian)
+    }
+
+    /// Inserts separators into the integer digits of `text`, or returns it unchanged when it is
+    /// not a number.
+    pub fn group<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.try_group(text).unwrap_or(Cow::Borrowed(text))
+    }
+
+    pub fn try_group<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, NumberError> {
+        let parts = parse_number(text, self.decimal)?;
+        let digits = &text[parts.integer.clone()];
+        let (first, rest) = self.style.sizes();
+        if digits.len() <= first {
+            return Ok(Cow::Borrowed(text));
+        }
+
+        let separators = (digits.len() - first).div_ceil(rest);
+        let mut grouped = String::with_capacity(text.len() + separators * self.separator.len_utf8());
+        grouped.push_str(&text[..parts.integer.start]);
+        for (idx, digit) in digits.char_indices() {
+            let remaining = digits.len() - idx;
+            if idx > usize::MIN && remaining >= first && (remaining - first).is_multiple_of(rest) {
+                grouped.push(self.separator);
+            }
+            grouped.push(digit);
+        }
+        grouped.push_str(&text[parts.integer.end..]);
+        Ok(Cow::Owned(grouped))
+    }
+
+    /// Removes the separators from `text`, or returns it unchanged when it is not a number
+    /// grouped in this style.
+    pub fn ungroup<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.try_ungroup(text).unwrap_or(Cow::Borrowed(text))
+    }
+
+    /// Like [`ungroup`](DigitGrouping::ungroup), but reports misplaced separators, e.g. the one
+    /// in `"12,34"`, and input that is not a number.
+    pub fn try_ungroup<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, NumberError> {
+        let start = sign_at(text, usize::MIN).map_or(usize::MIN, |sign| sign.end);
+        let end = text[start..]
+            .find(|c: char| !c.is_ascii_digit() && c != self.separator)
+            .map_or(text.len(), |pos| start + pos);
+
+        let mut groups = Vec::new();
+        let mut group_start = start;
+        for (pos, _) in text[start..end].match_indices(self.separator) {
+            groups.push(group_start..start + pos);
+            group_start = start + pos + self.separator.len_utf8();
+        }
+        groups.push(group_start..end);
+
+        let (first, rest) = self.style.sizes();
+        let separators = groups.len() - 1;
+        for (idx, group) in groups.iter().enumerate() {
+            let valid = match idx {
+                _ if separators == usize::MIN => true,
+                idx if idx == separators => group.len() == first,
+                usize::MIN => (1..=rest).contains(&group.len()),
+                _ => group.len() == rest,
+            };
+            if !valid {
+                // blame the separator next to the misplaced group
+                let separator = match idx {
+                    usize::MIN => group.end,
+                    _ => group.start - self.separator.len_utf8(),
+                };
+                return Err(NumberError::InvalidChar { position: char_position(text, separator) });
+            }
+        }
+
+        let ungrouped = join_ranges(text, std::iter::once(usize::MIN..start).chain(groups).chain(std::iter::once(end..text.len())));
+        parse_number(&ungrouped, self.decimal).map_err(|err| err.shifted(separators))?;
+        Ok(ungrouped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::prelude::*;
+    use crate::NumberError;
+
+    #[test]
+    fn keeps_integer_zeros() {
+        assert_eq!("100".normalize_number().to_string(), "100");
+        assert_eq!("1.000".normalize_number().to_string(), "1");
+        assert_eq!("1.".normalize_number().to_string(), "1");
+        assert_eq!("10.500".normalize_number().to_string(), "10.5");
+        assert_eq!("-0.0".normalize_number().to_string(), "-0");
+        assert_eq!(".000".normalize_number().to_string(), "0");
+        assert_eq!(".250".normalize_number().to_string(), ".25");
+        assert!(matches!("1.2300".normalize_number().as_cow(), Cow::Borrowed("1.23")));
+    }
+
+    #[test]
+    fn exponent_notation() {
+        assert_eq!("1.2300e10".normalize_number().to_string(), "1.23e10");
+        assert_eq!("5.0E-3".normalize_number().to_string(), "5E-3");
+        assert_eq!(
+            "+007.50e+010".normalize_number().strip_leading_zeros().strip_sign().to_string(),
+            "7.5e10"
+        );
+    }
+
+    #[test]
+    fn leading_zeros_and_signs() {
+        assert_eq!("007.50".normalize_number().to_string(), "007.5");
+        assert_eq!("007.50".normalize_number().strip_leading_zeros().to_string(), "7.5");
+        assert_eq!("000.50".normalize_number().strip_leading_zeros().to_string(), "0.5");
+        assert_eq!("0000".normalize_number().strip_leading_zeros().to_string(), "0");
+        assert_eq!("+12.0".normalize_number().strip_sign().to_string(), "12");
+        assert_eq!("-12.0".normalize_number().strip_sign().to_string(), "-12");
+        assert_eq!("-0.00".normalize_number().strip_sign().to_string(), "0");
+    }
+
+    #[test]
+    fn comma_decimal() {
+        assert_eq!("1,500".normalize_number().comma_decimal().to_string(), "1,5");
+        assert_eq!("1,000".normalize_number().comma_decimal().to_string(), "1");
+        assert_eq!("1٫50".normalize_number().decimal_separator('٫').to_string(), "1٫5");
+        assert_eq!(
+            "1.5".normalize_number().comma_decimal().try_to_string(),
+            Err(NumberError::InvalidChar { position: 1 })
+        );
+    }
+
+    #[test]
+    fn rejects_non_numeric_input() {
+        assert_eq!("karøbα0".normalize_number().to_string(), "karøbα0");
+        assert_eq!(
+            "karøbα0".normalize_number().try_to_string(),
+            Err(NumberError::InvalidChar { position: 0 })
+        );
+        assert_eq!("".normalize_number().try_to_string(), Err(NumberError::MissingDigits { position: 0 }));
+        assert_eq!("-.".normalize_number().try_to_string(), Err(NumberError::MissingDigits { position: 2 }));
+        assert_eq!("1e".normalize_number().try_to_string(), Err(NumberError::MissingDigits { position: 2 }));
+        assert_eq!("1.2.3".normalize_number().try_to_string(), Err(NumberError::InvalidChar { position: 3 }));
+        assert_eq!(" 1".normalize_number().try_to_string(), Err(NumberError::InvalidChar { position: 0 }));
+    }
+
+    #[test]
+    fn fixed_decimals_pad_and_round() {
+        assert_eq!("0.1".fixed_decimals(2).to_string(), "0.10");
+        assert_eq!("1".fixed_decimals(3).to_string(), "1.000");
+        assert_eq!(".5".fixed_decimals(1).to_string(), "0.5");
+        assert_eq!("2.345".fixed_decimals(2).to_string(), "2.35");
+        assert_eq!("-2.345".fixed_decimals(2).to_string(), "-2.35");
+        assert_eq!("9.995".fixed_decimals(2).to_string(), "10.00");
+        assert_eq!("99.5".fixed_decimals(0).to_string(), "100");
+        assert_eq!("-0.004".fixed_decimals(2).to_string(), "0.00");
+        assert_eq!("-0.001".fixed_decimals(2).to_string(), "0.00");
+        assert!(matches!("-0.00".fixed_decimals(2).as_cow(), Cow::Borrowed("-0.00")));
+        assert_eq!("-0.000".fixed_decimals(2).to_string(), "-0.00");
+        assert_eq!("-0".fixed_decimals(1).to_string(), "-0.0");
+        assert!(matches!("+1.50".fixed_decimals(2).as_cow(), Cow::Borrowed("+1.50")));
+        assert_eq!("+1.5".fixed_decimals(2).to_string(), "+1.50");
+        assert_eq!("+0.001".fixed_decimals(2).to_string(), "+0.00");
+        assert_eq!("12.".fixed_decimals(0).to_string(), "12");
+        assert!(matches!("3.14".fixed_decimals(2).as_cow(), Cow::Borrowed("3.14")));
+        assert_eq!(
+            "123456789012345678901234567890.125".fixed_decimals(2).to_string(),
+            "123456789012345678901234567890.13"
+        );
+    }
+
+    #[test]
+    fn fixed_decimals_rounding_modes() {
+        use crate::DecimalRounding;
+
+        let half_even = |text: &str, places| {
+            text.fixed_decimals(places).set_rounding(DecimalRounding::HalfEven).to_string()
+        };
+        assert_eq!(half_even("2.5", 0), "2");
+        assert_eq!(half_even("3.5", 0), "4");
+        assert_eq!(half_even("2.50001", 0), "3");
+        assert_eq!(half_even("0.125", 2), "0.12");
+        assert_eq!("2.349".fixed_decimals(2).set_rounding(DecimalRounding::TowardZero).to_string(), "2.34");
+        assert_eq!("1,005".fixed_decimals(2).comma_decimal().to_string(), "1,01");
+        assert_eq!(
+            "1.5e3".fixed_decimals(2).try_to_string(),
+            Err(NumberError::InvalidChar { position: 3 })
+        );
+        assert_eq!("karøbα".fixed_decimals(2).to_string(), "karøbα");
+    }
+
+    #[test]
+    fn group_digits() {
+        use crate::DigitGrouping;
+
+        let grouping = DigitGrouping::new();
+        assert_eq!(grouping.group("1234567.891"), "1,234,567.891");
+        assert_eq!(grouping.group("-123456"), "-123,456");
+        assert_eq!(grouping.group("1000"), "1,000");
+        assert!(matches!(grouping.group("999.5"), Cow::Borrowed("999.5")));
+        assert_eq!(grouping.group("karøbα"), "karøbα");
+
+        let german = DigitGrouping::new().separator('.').decimal_separator(',');
+        assert_eq!(german.group("1234567,89"), "1.234.567,89");
+        let swiss = DigitGrouping::new().separator('\'');
+        assert_eq!(swiss.group("1234567.5e3"), "1'234'567.5e3");
+
+        let indian = DigitGrouping::new().indian();
+        assert_eq!(indian.group("1234567.5"), "12,34,567.5");
+        assert_eq!(indian.group("123456789"), "12,34,56,789");
+        assert_eq!(indian.group("12345"), "12,345");
+        assert_eq!(indian.group("123"), "123");
+    }
+
+    #[test]
+    fn ungroup_digits() {
+        use crate::DigitGrouping;
+
+        let grouping = DigitGrouping::new();
+        assert_eq!(grouping.try_ungroup("1,234,567.891").unwrap(), "1234567.891");
+        assert_eq!(grouping.try_ungroup("-12,345").unwrap(), "-12345");
+        assert!(matches!(grouping.try_ungroup("1234.5"), Ok(Cow::Borrowed("1234.5"))));
+        assert!(matches!(grouping.ungroup("-999"), Cow::Borrowed("-999")));
+        assert_eq!(grouping.try_ungroup("12,34"), Err(NumberError::InvalidChar { position: 2 }));
+        assert_eq!(grouping.try_ungroup("1234,567"), Err(NumberError::InvalidChar { position: 4 }));
+        assert_eq!(grouping.try_ungroup("1,,234"), Err(NumberError::InvalidChar { position: 1 }));
+        assert_eq!(grouping.try_ungroup("1,234.5x"), Err(NumberError::InvalidChar { position: 7 }));
+        assert_eq!(grouping.ungroup("12,34"), "12,34");
+
+        let indian = DigitGrouping::new().indian();
+        assert_eq!(indian.try_ungroup("12,34,567.5").unwrap(), "1234567.5");
+        assert_eq!(indian.try_ungroup("1,234,567"), Err(NumberError::InvalidChar { position: 1 }));
+
+        let german = DigitGrouping::new().separator('.').decimal_separator(',');
+        assert_eq!(german.try_ungroup("1.234.567,89").unwrap(), "1234567,89");
+        for text in ["0", "999", "1000", "-98765432,1"] {
+            assert_eq!(german.try_ungroup(&german.group(text)).unwrap(), text);
+        }
+    }
+}
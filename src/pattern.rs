@@ -0,0 +1,405 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// A pattern a [`StringKeeper`](crate::StringKeeper) can search for, modelled on
+/// `std::str::pattern::Pattern`. All positions are byte offsets on char boundaries of `haystack`.
+pub trait KeeperPattern {
+    /// The leftmost match starting at or after `from`.
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>>;
+
+    /// The rightmost match ending at or before `to`.
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>>;
+
+    /// The end of a match starting exactly at `at`.
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self
+            .next_match(haystack, at)
+            .filter(|found| found.start == at)
+            .map(|found| found.end)
+    }
+
+    /// The start of a match ending exactly at `at`.
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self
+            .next_match_back(haystack, at)
+            .filter(|found| found.end == at)
+            .map(|found| found.start)
+    }
+
+    /// The text of a literal pattern, which lets [`KeeperMatching`](crate::KeeperMatching) modes
+    /// fold it. Patterns returning `None` always match exactly.
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+}
+
+fn next_char_match(haystack: &str, from: usize, mut pred: impl FnMut(char) -> bool) -> Option<Range<usize>> {
+    haystack[from..]
+        .char_indices()
+        .find(|(_, c)| pred(*c))
+        .map(|(pos, c)| from + pos..from + pos + c.len_utf8())
+}
+
+fn next_char_match_back(haystack: &str, to: usize, mut pred: impl FnMut(char) -> bool) -> Option<Range<usize>> {
+    haystack[..to]
+        .char_indices()
+        .rfind(|(_, c)| pred(*c))
+        .map(|(pos, c)| pos..pos + c.len_utf8())
+}
+
+impl KeeperPattern for str {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        haystack[from..]
+            .find(self)
+            .map(|pos| from + pos..from + pos + self.len())
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        haystack[..to]
+            .rfind(self)
+            .map(|pos| pos..pos + self.len())
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        haystack[at..]
+            .starts_with(self)
+            .then(|| at + self.len())
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        haystack[..at]
+            .ends_with(self)
+            .then(|| at - self.len())
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl KeeperPattern for &str {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        (**self).next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        (**self).next_match_back(haystack, to)
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        (**self).match_at(haystack, at)
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        (**self).match_back_at(haystack, at)
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl KeeperPattern for String {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self.as_str().next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self.as_str().next_match_back(haystack, to)
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self.as_str().match_at(haystack, at)
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self.as_str().match_back_at(haystack, at)
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.as_str()))
+    }
+}
+
+impl KeeperPattern for &String {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self.as_str().next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self.as_str().next_match_back(haystack, to)
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self.as_str().match_at(haystack, at)
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self.as_str().match_back_at(haystack, at)
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.as_str()))
+    }
+}
+
+impl KeeperPattern for char {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        haystack[from..]
+            .find(*self)
+            .map(|pos| from + pos..from + pos + self.len_utf8())
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        haystack[..to]
+            .rfind(*self)
+            .map(|pos| pos..pos + self.len_utf8())
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(self.to_string()))
+    }
+}
+
+impl KeeperPattern for &[char] {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        next_char_match(haystack, from, |c| self.contains(&c))
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        next_char_match_back(haystack, to, |c| self.contains(&c))
+    }
+}
+
+impl<const N: usize> KeeperPattern for [char; N] {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self.as_slice().next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self.as_slice().next_match_back(haystack, to)
+    }
+}
+
+impl<const N: usize> KeeperPattern for &[char; N] {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self.as_slice().next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self.as_slice().next_match_back(haystack, to)
+    }
+}
+
+impl<F: Fn(char) -> bool> KeeperPattern for F {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        next_char_match(haystack, from, self)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        next_char_match_back(haystack, to, self)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl KeeperPattern for regex::Regex {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        self.find_at(haystack, from).map(|found| found.range())
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        self
+            .find_iter(haystack)
+            .take_while(|found| found.end() <= to)
+            .last()
+            .map(|found| found.range())
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        self
+            .find_iter(haystack)
+            .take_while(|found| found.end() <= at)
+            .filter(|found| found.end() == at)
+            .last()
+            .map(|found| found.start())
+    }
+}
+
+#[cfg(feature = "regex")]
+impl KeeperPattern for &regex::Regex {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        (**self).next_match(haystack, from)
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        (**self).next_match_back(haystack, to)
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        (**self).match_back_at(haystack, at)
+    }
+}
+
+/// A pattern whose kind is only known at runtime, e.g. when it is loaded from a config file.
+#[derive(Clone, Debug)]
+pub enum RulePattern {
+    Str(String),
+    Char(char),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl PartialEq for RulePattern {
+    fn eq(&self, other: &RulePattern) -> bool {
+        match (self, other) {
+            (RulePattern::Str(a), RulePattern::Str(b)) => a == b,
+            (RulePattern::Char(a), RulePattern::Char(b)) => a == b,
+            #[cfg(feature = "regex")]
+            (RulePattern::Regex(a), RulePattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RulePattern {}
+
+impl KeeperPattern for RulePattern {
+    fn next_match(&self, haystack: &str, from: usize) -> Option<Range<usize>> {
+        match self {
+            RulePattern::Str(pattern) => pattern.next_match(haystack, from),
+            RulePattern::Char(pattern) => pattern.next_match(haystack, from),
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(pattern) => pattern.next_match(haystack, from),
+        }
+    }
+
+    fn next_match_back(&self, haystack: &str, to: usize) -> Option<Range<usize>> {
+        match self {
+            RulePattern::Str(pattern) => pattern.next_match_back(haystack, to),
+            RulePattern::Char(pattern) => pattern.next_match_back(haystack, to),
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(pattern) => pattern.next_match_back(haystack, to),
+        }
+    }
+
+    fn match_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        match self {
+            RulePattern::Str(pattern) => pattern.match_at(haystack, at),
+            RulePattern::Char(pattern) => pattern.match_at(haystack, at),
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(pattern) => pattern.match_at(haystack, at),
+        }
+    }
+
+    fn match_back_at(&self, haystack: &str, at: usize) -> Option<usize> {
+        match self {
+            RulePattern::Str(pattern) => pattern.match_back_at(haystack, at),
+            RulePattern::Char(pattern) => pattern.match_back_at(haystack, at),
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(pattern) => pattern.match_back_at(haystack, at),
+        }
+    }
+
+    fn literal(&self) -> Option<Cow<'_, str>> {
+        match self {
+            RulePattern::Str(pattern) => pattern.literal(),
+            RulePattern::Char(pattern) => pattern.literal(),
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn keep_with_str_pattern() {
+        assert_eq!(
+            "this is karøbα it was"
+                .to_string()
+                .keep("karøbα")
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            " it was"
+        );
+        assert_eq!(
+            "abc".to_string().keep("b").to_string(),
+            "bc"
+        );
+    }
+
+    #[test]
+    fn keep_with_char_set_pattern() {
+        assert_eq!(
+            "key=karøbα;rest"
+                .to_string()
+                .keep(['=', ':'])
+                .until_first_matched_pattern([';', ','])
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα"
+        );
+        assert_eq!(
+            "key:karøbα"
+                .to_string()
+                .keep(&['=', ':'][..])
+                .after_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα"
+        );
+    }
+
+    #[test]
+    fn keep_with_closure_pattern() {
+        let is_digit = |c: char| c.is_ascii_digit();
+        assert_eq!(
+            "karøbα 42 it was"
+                .to_string()
+                .keep(is_digit)
+                .until_no_matched_pattern(is_digit)
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "karøbα "
+        );
+        assert_eq!(
+            "karøbα 42 it was"
+                .to_string()
+                .cut(char::is_whitespace)
+                .end_of_string()
+                .before_pattern()
+                .including_pattern()
+                .to_string(),
+            "was"
+        );
+    }
+
+    #[test]
+    fn keep_with_borrowed_string_pattern() {
+        let pattern = "øbα".to_string();
+        assert_eq!(
+            "karøbα it was"
+                .to_string()
+                .keep(&pattern)
+                .before_pattern()
+                .excluding_pattern()
+                .to_string(),
+            "kar"
+        );
+    }
+
+    #[test]
+    fn keep_with_rule_pattern() {
+        use crate::RulePattern;
+
+        let patterns = [RulePattern::Str("øb".to_string()), RulePattern::Char('ø')];
+        for pattern in patterns {
+            assert_eq!("karøbα".keep(pattern).before_pattern().excluding_pattern().to_string(), "kar");
+        }
+    }
+}
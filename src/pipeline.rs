@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{KeeperError, KeeperPattern, KeeperSpec};
+
+/// An ordered list of keep/cut steps that can be built once and applied to many inputs.
+///
+/// Each step runs on the result of the previous one. As long as no `cut` has to join two separate
+/// pieces, the result is a slice of the original input and nothing is allocated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperPipeline<T> {
+    steps: Vec<KeeperSpec<T>>,
+}
+
+impl<T> Default for KeeperPipeline<T> {
+    fn default() -> Self {
+        KeeperPipeline { steps: Vec::new() }
+    }
+}
+
+impl<T> KeeperPipeline<T> {
+    pub fn new() -> KeeperPipeline<T> {
+        KeeperPipeline::default()
+    }
+
+    /// Appends a `keep(pattern)` step, configured like any other keeper:
+    /// `.keep('=', |step| step.after_pattern().excluding_pattern())`.
+    pub fn keep(self, pattern: T, configure: impl FnOnce(KeeperSpec<T>) -> KeeperSpec<T>) -> KeeperPipeline<T> {
+        self.then(configure(KeeperSpec::keep(pattern)))
+    }
+
+    /// Appends a `cut(pattern)` step.
+    pub fn cut(self, pattern: T, configure: impl FnOnce(KeeperSpec<T>) -> KeeperSpec<T>) -> KeeperPipeline<T> {
+        self.then(configure(KeeperSpec::cut(pattern)))
+    }
+
+    /// Appends a prepared step, e.g. `KeeperSpec::keep(',').end_of_string()`.
+    pub fn then(mut self, step: KeeperSpec<T>) -> KeeperPipeline<T> {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<T: KeeperPattern> KeeperPipeline<T> {
+    pub fn apply<'a>(&self, to_parse: &'a str) -> Cow<'a, str> {
+        self.try_apply(to_parse).unwrap_or_default()
+    }
+
+    /// Runs every step, stopping at the first one whose [`KeeperFallback`](crate::KeeperFallback)
+    /// is `Error` and that cannot select anything.
+    pub fn try_apply<'a>(&self, to_parse: &'a str) -> Result<Cow<'a, str>, KeeperError> {
+        let start = PipelineText::Slice(usize::MIN..to_parse.len());
+        let result = self.steps.iter().try_fold(start, |current, step| {
+            Ok(match current {
+                PipelineText::Slice(range) => {
+                    let (head, tail) = step.kept_ranges(&to_parse[range.clone()])?;
+                    let (head, tail) = (shift(head, range.start), shift(tail, range.start));
+                    if tail.is_empty() {
+                        PipelineText::Slice(head)
+                    } else if head.is_empty() {
+                        PipelineText::Slice(tail)
+                    } else {
+                        PipelineText::Joined([&to_parse[head], &to_parse[tail]].concat())
+                    }
+                }
+                PipelineText::Joined(mut text) => {
+                    // edit the buffer in place instead of allocating a new one
+                    let (head, tail) = step.kept_ranges(&text)?;
+                    text.truncate(tail.end);
+                    text.replace_range(head.end..tail.start, "");
+                    text.drain(..head.start);
+                    PipelineText::Joined(text)
+                }
+            })
+        })?;
+        Ok(match result {
+            PipelineText::Slice(range) => Cow::Borrowed(&to_parse[range]),
+            PipelineText::Joined(text) => Cow::Owned(text),
+        })
+    }
+}
+
+/// The intermediate result of a pipeline: a slice of the input until a cut joins two pieces.
+enum PipelineText {
+    Slice(Range<usize>),
+    Joined(String),
+}
+
+fn shift(range: Range<usize>, by: usize) -> Range<usize> {
+    range.start + by..range.end + by
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeeperPipeline;
+    use crate::prelude::*;
+    use crate::{KeeperError, KeeperFallback, KeeperSpec};
+    use std::borrow::Cow;
+
+    #[test]
+    fn pipeline_borrows_from_input() {
+        let pipeline = KeeperPipeline::new()
+            .keep('=', |step| step.after_pattern().excluding_pattern())
+            .keep(';', |step| step.before_pattern().excluding_pattern())
+            .cut(' ', |step| step.before_pattern().until_no_matched_pattern(' '));
+        let pipeline = pipeline.clone();
+        assert_eq!(pipeline.len(), 3);
+
+        let value = pipeline.apply("name = karøbα; it was");
+        assert!(matches!(value, Cow::Borrowed("karøbα")));
+        assert_eq!(pipeline.apply("x=  42;"), "42");
+        assert_eq!(pipeline.apply("karøbα"), "");
+    }
+
+    #[test]
+    fn pipeline_matches_chained_keepers() {
+        let pipeline = KeeperPipeline::new()
+            .then(KeeperSpec::cut("--").end_of_string().after_pattern())
+            .keep("/", |step| step.end_of_string().after_pattern().excluding_pattern());
+        let text = "/var/log/karøbα.log -- rotated";
+        let chained = text
+            .cut("--")
+            .end_of_string()
+            .after_pattern()
+            .to_string()
+            .keep("/")
+            .end_of_string()
+            .after_pattern()
+            .excluding_pattern()
+            .to_string();
+        assert_eq!(pipeline.apply(text), chained);
+        assert_eq!(pipeline.apply(text), "karøbα.log ");
+    }
+
+    #[test]
+    fn pipeline_joins_cut_pieces() {
+        let pipeline = KeeperPipeline::new()
+            .cut("r", |step| step.until_first_matched_pattern("b"))
+            .keep("α", |step| step.before_pattern());
+        assert_eq!(pipeline.apply("karøbα it was"), "kaα");
+        assert_eq!(KeeperPipeline::<char>::new().apply("karøbα"), "karøbα");
+    }
+
+    #[test]
+    fn keep_only_pipeline_borrows_input() {
+        let pipeline = KeeperPipeline::new()
+            .keep("[", |step| step.after_pattern().excluding_pattern())
+            .keep("]", |step| step.end_of_string().before_pattern().excluding_pattern())
+            .keep(":", |step| step.after_pattern().excluding_pattern());
+        let text = String::from("log [warn: karøbα] it was");
+        let kept = pipeline.apply(&text);
+        assert_eq!(kept, " karøbα");
+        assert!(matches!(kept, Cow::Borrowed(_)));
+        assert!(std::ptr::eq(kept.as_ptr(), text[10..].as_ptr()));
+    }
+
+    #[test]
+    fn pipeline_edits_joined_text_in_place() {
+        let pipeline = KeeperPipeline::new()
+            .cut(" ", |step| step.until_first_matched_pattern(" "))
+            .cut("a", |step| step.excluding_pattern().until_first_matched_pattern("s"));
+        assert_eq!(pipeline.apply("ka karøbα was"), "kas");
+        let pipeline = pipeline.keep("a", |step| step.end_of_string().after_pattern().excluding_pattern());
+        assert_eq!(pipeline.apply("ka karøbα was"), "s");
+    }
+
+    #[test]
+    fn pipeline_error_fallback() {
+        let pipeline = KeeperPipeline::new()
+            .keep('(', |step| step.set_fallback(KeeperFallback::Error))
+            .keep(')', |step| step.before_pattern());
+        assert_eq!(pipeline.try_apply("f(x) y"), Ok(Cow::Borrowed("(x)")));
+        assert_eq!(pipeline.try_apply("karøbα"), Err(KeeperError::PatternNotFound));
+    }
+}
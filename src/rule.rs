@@ -0,0 +1,391 @@
+use crate::{
+    KeeperClusivity, KeeperCutoff, KeeperEncoding, KeeperFallback, KeeperMatching, KeeperPeriod, KeeperRule,
+    KeeperSpec, KeeperSpecCommonExt, KeeperUntilMatch, RuleParseError, RulePattern, StringKeeperMode,
+};
+
+struct RuleParser<'a> {
+    chars: std::iter::Peekable<std::iter::Enumerate<std::str::Chars<'a>>>,
+    len: usize,
+}
+
+impl RuleParser<'_> {
+    fn error<T>(position: usize, message: impl Into<String>) -> Result<T, RuleParseError> {
+        Err(RuleParseError { position, message: message.into() })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn position(&mut self) -> usize {
+        self.skip_whitespace();
+        self.chars.peek().map_or(self.len, |(pos, _)| *pos)
+    }
+
+    fn word(&mut self) -> Option<(usize, String)> {
+        let position = self.position();
+        let mut word = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '-') {
+            word.push(c);
+        }
+        (!word.is_empty()).then_some((position, word))
+    }
+
+    fn expect_word(&mut self, expected: &str) -> Result<(usize, String), RuleParseError> {
+        let position = self.position();
+        match self.word() {
+            Some(found) => Ok(found),
+            None => Self::error(position, format!("expected {}", expected)),
+        }
+    }
+
+    fn quoted(&mut self, raw: bool) -> Result<String, RuleParseError> {
+        let position = self.position();
+        if self.chars.next_if(|(_, c)| *c == '\'').is_none() {
+            return Self::error(position, "expected `'`");
+        }
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                None => return Self::error(position, "unterminated quote"),
+                Some((_, '\'')) => return Ok(text),
+                Some((pos, '\\')) => match self.chars.peek().map(|(_, c)| *c) {
+                    Some('\'') => text.push('\''),
+                    Some(c) if raw => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    Some('\\') => text.push('\\'),
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    _ => return Self::error(pos, "unknown escape"),
+                },
+                Some((_, c)) => {
+                    text.push(c);
+                    continue;
+                }
+            }
+            self.chars.next();
+        }
+    }
+
+    fn at_pattern(&mut self) -> bool {
+        self.skip_whitespace();
+        let mut lookahead = self.chars.clone().map(|(_, c)| c);
+        match lookahead.next() {
+            Some('\'') => true,
+            Some('c' | 'r') => lookahead.next() == Some('\''),
+            _ => false,
+        }
+    }
+
+    fn pattern(&mut self) -> Result<RulePattern, RuleParseError> {
+        let position = self.position();
+        let prefix = self.chars.next_if(|(_, c)| *c == 'c' || *c == 'r').map(|(_, c)| c);
+        let text = self.quoted(prefix == Some('r'))?;
+        match prefix {
+            None => Ok(RulePattern::Str(text)),
+            Some('c') => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(RulePattern::Char(c)),
+                    _ => Self::error(position, "char pattern must be a single char"),
+                }
+            }
+            #[cfg(feature = "regex")]
+            _ => regex::Regex::new(&text)
+                .map(RulePattern::Regex)
+                .or_else(|err| Self::error(position, format!("invalid regex: {}", err))),
+            #[cfg(not(feature = "regex"))]
+            _ => Self::error(position, "regex patterns require the `regex` feature"),
+        }
+    }
+}
+
+/// Parses a keeper rule from a small text language:
+///
+/// ```text
+/// rule    = ("keep" | "cut") [side] pattern clause*
+/// side    = "after" | "before" | "after-incl" | "after-excl" | "before-incl" | "before-excl"
+/// clause  = "from" ("start" | "end")
+///         | ("until-first" | "until-none") pattern
+///         | "nth" number
+///         | "fallback" ("empty" | "whole-input" | "error")
+///         | "encoding" ("utf8" | "utf16")
+///         | "match" ("exact" | "ascii-case" | "case-fold" | "nfc" | "nfkc")
+/// pattern = "'text'" | "c'x'" | "r'regex'"
+/// ```
+///
+/// Inside `'text'` and `c'x'`, `\'`, `\\`, `\n` and `\t` are escapes. Inside `r'regex'` only `\'`
+/// is; any other backslash is kept together with the char after it, so the regex keeps its own
+/// escapes.
+impl std::str::FromStr for KeeperSpec<RulePattern> {
+    type Err = RuleParseError;
+
+    fn from_str(rule: &str) -> Result<KeeperRule, RuleParseError> {
+        let mut parser = RuleParser { chars: rule.chars().enumerate().peekable(), len: rule.chars().count() };
+
+        let (position, mode) = parser.expect_word("`keep` or `cut`")?;
+        let mode = match mode.as_str() {
+            "keep" => KeeperSpec::keep,
+            "cut" => KeeperSpec::cut,
+            _ => return RuleParser::error(position, "expected `keep` or `cut`"),
+        };
+
+        let side = if parser.at_pattern() {
+            (KeeperCutoff::After, KeeperClusivity::Including)
+        } else {
+            match parser.expect_word("a side or a pattern")? {
+                (_, side) if side == "after" || side == "after-incl" => (KeeperCutoff::After, KeeperClusivity::Including),
+                (_, side) if side == "after-excl" => (KeeperCutoff::After, KeeperClusivity::Excluding),
+                (_, side) if side == "before" || side == "before-incl" => (KeeperCutoff::Before, KeeperClusivity::Including),
+                (_, side) if side == "before-excl" => (KeeperCutoff::Before, KeeperClusivity::Excluding),
+                (position, side) => return RuleParser::error(position, format!("unknown side `{}`", side)),
+            }
+        };
+        let mut spec = mode(parser.pattern()?);
+        spec.opt.cutoff = side.0;
+        spec.opt.clusivity = side.1;
+
+        let mut seen = Vec::new();
+        while let Some((position, clause)) = parser.word() {
+            if seen.contains(&clause) || (clause.starts_with("until-") && seen.iter().any(|seen| seen.starts_with("until-"))) {
+                return RuleParser::error(position, format!("duplicate `{}` clause", clause));
+            }
+            spec = match clause.as_str() {
+                "from" => match parser.expect_word("`start` or `end`")? {
+                    (_, period) if period == "start" => spec.beginning_of_string(),
+                    (_, period) if period == "end" => spec.end_of_string(),
+                    (position, _) => return RuleParser::error(position, "expected `start` or `end`"),
+                },
+                "until-first" => spec.until_first_matched_pattern(parser.pattern()?),
+                "until-none" => spec.until_no_matched_pattern(parser.pattern()?),
+                "nth" => {
+                    let (position, n) = parser.expect_word("a number")?;
+                    match n.parse() {
+                        Ok(n) => spec.nth_occurrence(n),
+                        Err(_) => return RuleParser::error(position, "expected a number"),
+                    }
+                }
+                "fallback" => match parser.expect_word("a fallback")? {
+                    (_, fallback) if fallback == "empty" => spec.set_fallback(KeeperFallback::Empty),
+                    (_, fallback) if fallback == "whole-input" => spec.set_fallback(KeeperFallback::WholeInput),
+                    (_, fallback) if fallback == "error" => spec.set_fallback(KeeperFallback::Error),
+                    (position, _) => return RuleParser::error(position, "expected `empty`, `whole-input` or `error`"),
+                },
+                "encoding" => match parser.expect_word("an encoding")? {
+                    (_, encoding) if encoding == "utf8" => spec.utf8_encoding(),
+                    (_, encoding) if encoding == "utf16" => spec.utf16_encoding(),
+                    (position, _) => return RuleParser::error(position, "expected `utf8` or `utf16`"),
+                },
+                "match" => {
+                    let (position, matching) = parser.expect_word("a matching mode")?;
+                    match matching.as_str() {
+                        "exact" => spec.set_matching(KeeperMatching::Exact),
+                        "ascii-case" => spec.set_matching(KeeperMatching::AsciiCaseInsensitive),
+                        #[cfg(feature = "caseless")]
+                        "case-fold" => spec.set_matching(KeeperMatching::CaseFold),
+                        #[cfg(feature = "unicode-normalization")]
+                        "nfc" => spec.set_matching(KeeperMatching::Nfc),
+                        #[cfg(feature = "unicode-normalization")]
+                        "nfkc" => spec.set_matching(KeeperMatching::Nfkc),
+                        #[cfg(not(feature = "caseless"))]
+                        "case-fold" => return RuleParser::error(position, "`case-fold` requires the `caseless` feature"),
+                        #[cfg(not(feature = "unicode-normalization"))]
+                        "nfc" | "nfkc" => {
+                            return RuleParser::error(
+                                position,
+                                format!("`{}` requires the `unicode-normalization` feature", matching),
+                            );
+                        }
+                        _ => return RuleParser::error(position, format!("unknown matching mode `{}`", matching)),
+                    }
+                }
+                _ => return RuleParser::error(position, format!("unknown clause `{}`", clause)),
+            };
+            seen.push(clause);
+        }
+
+        let position = parser.position();
+        if position < parser.len {
+            return RuleParser::error(position, "unexpected input");
+        }
+        Ok(spec)
+    }
+}
+
+fn write_quoted(f: &mut std::fmt::Formatter<'_>, text: &str, raw: bool) -> std::fmt::Result {
+    write!(f, "'")?;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => write!(f, "\\'")?,
+            '\\' if raw => match chars.next() {
+                Some('\'') | None => write!(f, "\\'")?,
+                Some(c) => write!(f, "\\{}", c)?,
+            },
+            '\\' if !raw => write!(f, "\\\\")?,
+            '\n' if !raw => write!(f, "\\n")?,
+            '\t' if !raw => write!(f, "\\t")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "'")
+}
+
+impl std::fmt::Display for RulePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulePattern::Str(text) => write_quoted(f, text, false),
+            RulePattern::Char(c) => {
+                write!(f, "c")?;
+                write_quoted(f, c.encode_utf8(&mut [0; 4]), false)
+            }
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(regex) => {
+                write!(f, "r")?;
+                write_quoted(f, regex.as_str(), true)
+            }
+        }
+    }
+}
+
+/// Writes the rule in canonical form, which parses back into an equal rule.
+impl std::fmt::Display for KeeperSpec<RulePattern> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self.opt.mode {
+            StringKeeperMode::Keep => "keep",
+            StringKeeperMode::Cut => "cut",
+        };
+        let side = match (&self.opt.cutoff, &self.opt.clusivity) {
+            (KeeperCutoff::After, KeeperClusivity::Including) => "after-incl",
+            (KeeperCutoff::After, KeeperClusivity::Excluding) => "after-excl",
+            (KeeperCutoff::Before, KeeperClusivity::Including) => "before-incl",
+            (KeeperCutoff::Before, KeeperClusivity::Excluding) => "before-excl",
+        };
+        let period = match self.opt.period {
+            KeeperPeriod::Start => "start",
+            KeeperPeriod::End => "end",
+        };
+        write!(f, "{} {} {} from {}", mode, side, self.pattern, period)?;
+
+        match (&self.until_pattern, &self.opt.until_match) {
+            (Some(until_pattern), Some(KeeperUntilMatch::FirstMatch)) => write!(f, " until-first {}", until_pattern)?,
+            (Some(until_pattern), Some(KeeperUntilMatch::NoMatch)) => write!(f, " until-none {}", until_pattern)?,
+            _ => {}
+        }
+        if self.opt.occurrence != usize::MIN {
+            write!(f, " nth {}", self.opt.occurrence)?;
+        }
+        let default_fallback = match self.opt.mode {
+            StringKeeperMode::Keep => KeeperFallback::Empty,
+            StringKeeperMode::Cut => KeeperFallback::WholeInput,
+        };
+        if self.opt.fallback != default_fallback {
+            let fallback = match self.opt.fallback {
+                KeeperFallback::Empty => "empty",
+                KeeperFallback::WholeInput => "whole-input",
+                KeeperFallback::Error => "error",
+            };
+            write!(f, " fallback {}", fallback)?;
+        }
+        match self.opt.encoding {
+            Some(KeeperEncoding::Utf8) => write!(f, " encoding utf8")?,
+            Some(KeeperEncoding::Utf16) => write!(f, " encoding utf16")?,
+            None => {}
+        }
+        match self.opt.matching {
+            KeeperMatching::Exact => Ok(()),
+            KeeperMatching::AsciiCaseInsensitive => write!(f, " match ascii-case"),
+            #[cfg(feature = "caseless")]
+            KeeperMatching::CaseFold => write!(f, " match case-fold"),
+            #[cfg(feature = "unicode-normalization")]
+            KeeperMatching::Nfc => write!(f, " match nfc"),
+            #[cfg(feature = "unicode-normalization")]
+            KeeperMatching::Nfkc => write!(f, " match nfkc"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{KeeperFallback, KeeperRule, KeeperSpec, RuleParseError, RulePattern};
+
+    fn parse(rule: &str) -> KeeperRule {
+        rule.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_rules() {
+        let rule = parse("keep after-excl 'karøbα' from end until-first ','");
+        let expected = KeeperSpec::keep(RulePattern::Str("karøbα".to_string()))
+            .after_pattern()
+            .excluding_pattern()
+            .end_of_string()
+            .until_first_matched_pattern(RulePattern::Str(",".to_string()));
+        assert_eq!(rule, expected);
+        assert_eq!(rule.apply("karøbα 1, karøbα 2, 3"), " 2");
+
+        assert_eq!(parse("keep '='"), KeeperSpec::keep(RulePattern::Str("=".to_string())));
+        assert_eq!(parse("cut c'0'  from end until-none c'0'").apply("1.2500"), "1.25");
+        assert_eq!(parse("keep before c'='").apply("k=v"), "k=");
+        assert_eq!(
+            parse("keep after-excl c'=' nth 1 fallback error encoding utf16"),
+            KeeperSpec::keep(RulePattern::Char('='))
+                .after_pattern()
+                .excluding_pattern()
+                .nth_occurrence(1)
+                .set_fallback(KeeperFallback::Error)
+                .utf16_encoding()
+        );
+        assert_eq!(parse(r"keep 'it\'s \\ \n'").apply("it's \\ \n"), "it's \\ \n");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let rules = [
+            "keep after-excl 'karøbα' from end until-first ','",
+            "cut before-incl c'\\'' from start until-none c' ' nth 2",
+            "keep after-incl 'a\\\\b\\n' from start fallback whole-input encoding utf8",
+            "cut after-incl '' from end fallback empty",
+            "keep after-excl 'error:' from start match ascii-case",
+        ];
+        for rule in rules {
+            assert_eq!(parse(rule).to_string(), rule);
+            assert_eq!(parse(&parse(rule).to_string()), parse(rule));
+        }
+        assert_eq!(parse("keep '='").to_string(), "keep after-incl '=' from start");
+    }
+
+    #[test]
+    fn positioned_errors() {
+        let error = |rule: &str| rule.parse::<KeeperRule>().unwrap_err();
+        assert_eq!(error(""), RuleParseError { position: 0, message: "expected `keep` or `cut`".to_string() });
+        assert_eq!(error("take 'x'").position, 0);
+        assert_eq!(error("keep sideways 'x'"), RuleParseError {
+            position: 5,
+            message: "unknown side `sideways`".to_string(),
+        });
+        assert_eq!(error("keep x").message, "unknown side `x`");
+        assert_eq!(error("keep 'kar"), RuleParseError { position: 5, message: "unterminated quote".to_string() });
+        assert_eq!(error("keep c'øb'").position, 5);
+        assert_eq!(error("keep 'ø' from middle").position, 14);
+        assert_eq!(error("keep 'ø' nth x").message, "expected a number");
+        assert_eq!(error("keep 'ø' from end from start").message, "duplicate `from` clause");
+        assert_eq!(error("keep 'ø' until-first 'a' until-none 'b'").position, 25);
+        assert_eq!(error("keep 'ø' 'x'").to_string(), "unexpected input at position 9");
+        assert_eq!(error(r"keep 'a\q'").position, 7);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_rules() {
+        let rule = parse(r"cut r'\d+(\.\d+)?' from end");
+        assert_eq!(rule.apply("karøbα 1.5 it was 2.25"), "karøbα 1.5 it was ");
+        assert_eq!(rule.to_string(), r"cut after-incl r'\d+(\.\d+)?' from end");
+        assert_eq!(parse(r"keep r'\'\\'").to_string(), r"keep after-incl r'\'\\' from start");
+        assert_eq!(parse(r"keep r'it\'s'").apply("karøbα it's"), "it's");
+        assert!("keep r'(ø'".parse::<KeeperRule>().unwrap_err().message.starts_with("invalid regex"));
+    }
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    KeeperClusivity, KeeperCutoff, KeeperEncoding, KeeperFallback, KeeperMatching, KeeperPeriod, KeeperSpec,
+    KeeperUntilMatch, RulePattern, StringKeeperMode, StringKeeperOpts,
+};
+
+/// Every option may be omitted; the defaults are those of `keep`/`cut` for the given mode.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct StringKeeperOptsDef {
+    until_match: Option<KeeperUntilMatch>,
+    mode: Option<StringKeeperMode>,
+    period: Option<KeeperPeriod>,
+    clusivity: Option<KeeperClusivity>,
+    cutoff: Option<KeeperCutoff>,
+    encoding: Option<KeeperEncoding>,
+    fallback: Option<KeeperFallback>,
+    occurrence: Option<usize>,
+    matching: Option<KeeperMatching>,
+}
+
+impl From<StringKeeperOptsDef> for StringKeeperOpts {
+    fn from(def: StringKeeperOptsDef) -> StringKeeperOpts {
+        let defaults = match def.mode {
+            Some(StringKeeperMode::Cut) => KeeperSpec::cut(()).opt,
+            Some(StringKeeperMode::Keep) | None => KeeperSpec::keep(()).opt,
+        };
+        StringKeeperOpts {
+            until_match: def.until_match.or(defaults.until_match),
+            mode: defaults.mode,
+            period: def.period.unwrap_or(defaults.period),
+            clusivity: def.clusivity.unwrap_or(defaults.clusivity),
+            cutoff: def.cutoff.unwrap_or(defaults.cutoff),
+            encoding: def.encoding.or(defaults.encoding),
+            fallback: def.fallback.unwrap_or(defaults.fallback),
+            occurrence: def.occurrence.unwrap_or(defaults.occurrence),
+            matching: def.matching.unwrap_or(defaults.matching),
+        }
+    }
+}
+
+/// The options are listed rather than flattened, so that misspelled keys are rejected. An until
+/// pattern without an explicit `until_match` bounds the first match.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct KeeperSpecDef<T> {
+    pattern: T,
+    until_pattern: Option<T>,
+    until_match: Option<KeeperUntilMatch>,
+    mode: Option<StringKeeperMode>,
+    period: Option<KeeperPeriod>,
+    clusivity: Option<KeeperClusivity>,
+    cutoff: Option<KeeperCutoff>,
+    encoding: Option<KeeperEncoding>,
+    fallback: Option<KeeperFallback>,
+    occurrence: Option<usize>,
+    matching: Option<KeeperMatching>,
+}
+
+impl<T> TryFrom<KeeperSpecDef<T>> for KeeperSpec<T> {
+    type Error = String;
+
+    fn try_from(def: KeeperSpecDef<T>) -> Result<KeeperSpec<T>, String> {
+        let until_match = match (&def.until_pattern, def.until_match) {
+            (None, Some(_)) => return Err("`until_match` requires an `until_pattern`".to_string()),
+            (Some(_), None) => Some(KeeperUntilMatch::FirstMatch),
+            (_, until_match) => until_match,
+        };
+        let opt = StringKeeperOpts::from(StringKeeperOptsDef {
+            until_match,
+            mode: def.mode,
+            period: def.period,
+            clusivity: def.clusivity,
+            cutoff: def.cutoff,
+            encoding: def.encoding,
+            fallback: def.fallback,
+            occurrence: def.occurrence,
+            matching: def.matching,
+        });
+        Ok(KeeperSpec { pattern: def.pattern, until_pattern: def.until_pattern, opt })
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RulePatternKind {
+    String,
+    Char,
+    Regex,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RulePatternDef {
+    kind: RulePatternKind,
+    text: String,
+}
+
+impl Serialize for RulePattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let def = match self {
+            RulePattern::Str(text) => RulePatternDef { kind: RulePatternKind::String, text: text.clone() },
+            RulePattern::Char(c) => RulePatternDef { kind: RulePatternKind::Char, text: c.to_string() },
+            #[cfg(feature = "regex")]
+            RulePattern::Regex(regex) => RulePatternDef { kind: RulePatternKind::Regex, text: regex.as_str().to_string() },
+        };
+        def.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RulePattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<RulePattern, D::Error> {
+        use serde::de::Error;
+
+        let def = RulePatternDef::deserialize(deserializer)?;
+        match def.kind {
+            RulePatternKind::String => Ok(RulePattern::Str(def.text)),
+            RulePatternKind::Char => {
+                let mut chars = def.text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(RulePattern::Char(c)),
+                    _ => Err(D::Error::custom(format!("char pattern must be a single char, got {:?}", def.text))),
+                }
+            }
+            #[cfg(feature = "regex")]
+            RulePatternKind::Regex => regex::Regex::new(&def.text)
+                .map(RulePattern::Regex)
+                .map_err(|err| D::Error::custom(format!("invalid regex pattern {:?}: {}", def.text, err))),
+            #[cfg(not(feature = "regex"))]
+            RulePatternKind::Regex => Err(D::Error::custom(format!(
+                "regex pattern {:?} requires the `regex` feature",
+                def.text
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{KeeperCutoff, KeeperFallback, KeeperRule, KeeperSpec, RulePattern, StringKeeperOpts};
+
+    #[test]
+    fn options_round_trip() {
+        let spec = KeeperSpec::keep(',').end_of_string().excluding_pattern().set_fallback(KeeperFallback::Error);
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(
+            json,
+            r#"{"pattern":",","mode":"keep","period":"end","clusivity":"excluding","cutoff":"after","fallback":"error","occurrence":0,"matching":"exact"}"#
+        );
+        assert_eq!(serde_json::from_str::<KeeperSpec<char>>(&json).unwrap(), spec);
+        assert_eq!(serde_json::to_string(&KeeperCutoff::Before).unwrap(), r#""before""#);
+    }
+
+    #[test]
+    fn omitted_options_use_mode_defaults() {
+        let opts = serde_json::from_str::<StringKeeperOpts>(r#"{"mode":"cut"}"#).unwrap();
+        assert_eq!(opts, KeeperSpec::cut(()).opt);
+        let opts = serde_json::from_str::<StringKeeperOpts>("{}").unwrap();
+        assert_eq!(opts, KeeperSpec::keep(()).opt);
+    }
+
+    #[test]
+    fn rule_from_yaml() {
+        let rule = serde_yaml::from_str::<KeeperRule>(
+            "
+pattern: { kind: string, text: '=' }
+until_pattern: { kind: char, text: ';' }
+cutoff: after
+clusivity: excluding
+",
+        )
+        .unwrap();
+        let expected = KeeperSpec::keep(RulePattern::Str("=".to_string()))
+            .after_pattern()
+            .excluding_pattern()
+            .until_first_matched_pattern(RulePattern::Char(';'));
+        assert_eq!(rule, expected);
+        assert_eq!(rule.apply("name=karøbα; it was"), "karøbα");
+
+        let rule = serde_yaml::from_str::<KeeperRule>(
+            "
+pattern: { kind: string, text: 'error:' }
+matching: ascii_case_insensitive
+clusivity: excluding
+",
+        )
+        .unwrap();
+        assert_eq!(rule.apply("[12] ERROR: karøbα"), " karøbα");
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let err = serde_json::from_str::<KeeperRule>(r#"{"pattern":{"kind":"char","text":"øb"}}"#).unwrap_err();
+        assert!(err.to_string().contains(r#"char pattern must be a single char, got "øb""#), "{}", err);
+        let err = serde_json::from_str::<KeeperRule>(r#"{"pattern":{"kind":"word","text":"a"}}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `word`"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = serde_json::from_str::<KeeperSpec<char>>(r#"{"pattern":"=","untill_pattern":";"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown field `untill_pattern`"), "{}", err);
+        let err = serde_json::from_str::<StringKeeperOpts>(r#"{"mode":"cut","perod":"end"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown field `perod`"), "{}", err);
+        let err = serde_json::from_str::<KeeperRule>(r#"{"pattern":{"kind":"string","text":"x","txet":"y"}}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown field `txet`"), "{}", err);
+    }
+
+    #[test]
+    fn until_match_requires_until_pattern() {
+        let err = serde_json::from_str::<KeeperSpec<char>>(r#"{"pattern":"=","until_match":"no_match"}"#).unwrap_err();
+        assert!(err.to_string().contains("`until_match` requires an `until_pattern`"), "{}", err);
+        let spec = serde_json::from_str::<KeeperSpec<char>>(r#"{"pattern":"=","until_pattern":" ","until_match":"no_match"}"#)
+            .unwrap();
+        assert_eq!(spec, KeeperSpec::keep('=').until_no_matched_pattern(' '));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_compiled_on_load() {
+        let rule = serde_json::from_str::<KeeperRule>(
+            r#"{"pattern":{"kind":"regex","text":"\\d+"},"mode":"cut","period":"end"}"#,
+        )
+        .unwrap();
+        assert_eq!(rule.apply("karøbα 12 it was 345"), "karøbα 12 it was ");
+        assert_eq!(
+            serde_json::to_string(&rule).unwrap(),
+            r#"{"pattern":{"kind":"regex","text":"\\d+"},"mode":"cut","period":"end","clusivity":"including","cutoff":"after","fallback":"whole_input","occurrence":0,"matching":"exact"}"#
+        );
+
+        let err = serde_json::from_str::<KeeperRule>(r#"{"pattern":{"kind":"regex","text":"(ø"}}"#).unwrap_err();
+        assert!(err.to_string().contains(r#"invalid regex pattern "(ø""#), "{}", err);
+    }
+}
@@ -0,0 +1,34 @@
+use std::ops::Range;
+
+/// A region of the input expressed in bytes, chars and, when requested, UTF-16 code units.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KeeperSpan {
+    pub bytes: Range<usize>,
+    pub chars: Range<usize>,
+    pub utf16: Option<Range<usize>>,
+}
+
+/// Where a [`StringKeeper`](crate::StringKeeper) selection lies in the original input.
+///
+/// `kept` is the selected region: the text that is kept in keep mode and removed in cut mode.
+/// `pattern` is the matched pattern, widened by any `until_no_matched_pattern` run.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KeeperResolution {
+    pub kept: KeeperSpan,
+    pub pattern: KeeperSpan,
+    pub until_pattern: Option<KeeperSpan>,
+}
+
+impl KeeperSpan {
+    pub(crate) fn new(text: &str, bytes: Range<usize>, utf16: bool) -> KeeperSpan {
+        let chars = to_units(text, &bytes, |_| 1);
+        let utf16 = utf16.then(|| to_units(text, &bytes, char::len_utf16));
+        KeeperSpan { bytes, chars, utf16 }
+    }
+}
+
+fn to_units(text: &str, bytes: &Range<usize>, width: impl Fn(char) -> usize) -> Range<usize> {
+    let start = text[..bytes.start].chars().map(&width).sum::<usize>();
+    let len = text[bytes.clone()].chars().map(&width).sum::<usize>();
+    start..start + len
+}